@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::{Serialize, Deserialize};
+
+use crate::error::Result;
+use crate::faults::{self, Fault};
+use crate::journal::write_atomic;
+use crate::paths::get_weave_directory;
+use crate::ClientType;
+
+// Every game launched through the manager is recorded into ~/.weave/analytics.json when it
+// exits. The launch time runs from spawning the process until the sound engine has started,
+// which is the last step before the main menu shows up on every supported version.
+
+const LAUNCHED_MARKER: &str = "Sound engine started";
+const CRASH_MARKERS: [&str; 3] = [
+    "---- Minecraft Crash Report ----",
+    "#@!@# Game crashed!",
+    // the JVM itself crashed, it aborts instead of exiting with a code
+    "# A fatal error has been detected by the Java Runtime Environment"
+];
+
+// only the most recent launches count towards the average
+const MAX_LAUNCH_TIMES: usize = 20;
+
+// serializes read-modify-write cycles of analytics.json between exiting instances
+static ANALYTICS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    // milliseconds, oldest first
+    launch_times: Vec<u32>,
+    // milliseconds
+    time_played: u64,
+    // seconds, -1 while there are no launch times
+    average_launch_time: f32,
+    sessions: u32,
+    crashes: u32
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            launch_times: Vec::new(),
+            time_played: 0,
+            average_launch_time: -1.0,
+            sessions: 0,
+            crashes: 0
+        }
+    }
+}
+
+impl Stats {
+    fn add(&mut self, session: &Session, played: Duration, crashed: bool) {
+        if let Some(launch_time) = session.launch_time {
+            self.launch_times.push(u32::try_from(launch_time.as_millis()).unwrap_or(u32::MAX));
+            if self.launch_times.len() > MAX_LAUNCH_TIMES {
+                self.launch_times.drain(..self.launch_times.len() - MAX_LAUNCH_TIMES);
+            }
+            let total: f32 = self.launch_times.iter().map(|&time| time as f32).sum();
+            self.average_launch_time = total / self.launch_times.len() as f32 / 1000.0;
+        }
+        self.time_played += u64::try_from(played.as_millis()).unwrap_or(u64::MAX);
+        self.sessions += 1;
+        if crashed {
+            self.crashes += 1;
+        }
+    }
+}
+
+// the totals keep the fields the frontend has always read at the top level
+#[derive(Default, Serialize, Deserialize)]
+pub struct Analytics {
+    #[serde(flatten)]
+    total: Stats,
+    #[serde(default)]
+    clients: BTreeMap<ClientType, Stats>
+}
+
+// one running game, fed its console output until it exits
+pub struct Session {
+    client: ClientType,
+    started: Instant,
+    launch_time: Option<Duration>,
+    crash_reported: bool
+}
+
+impl Session {
+    pub fn start(client: ClientType) -> Self {
+        Self {
+            client,
+            started: Instant::now(),
+            launch_time: None,
+            crash_reported: false
+        }
+    }
+
+    pub fn inspect_line(&mut self, line: &str) {
+        if self.launch_time.is_none() && line.contains(LAUNCHED_MARKER) {
+            self.launch_time = Some(self.started.elapsed());
+        }
+        if CRASH_MARKERS.iter().any(|marker| line.contains(marker)) {
+            self.crash_reported = true;
+        }
+    }
+
+    // Stopping the game through kill_pid or an auto-attach relaunch is not a crash, even though
+    // it ends without an exit code. Other exits only count when they report a failure.
+    pub fn crashed(&self, exit_code: Option<i32>, killed: bool) -> bool {
+        self.crash_reported || (!killed && exit_code.is_some_and(|code| code != 0))
+    }
+}
+
+fn get_analytics_path() -> Result<PathBuf> {
+    Ok(get_weave_directory()?.join("analytics.json"))
+}
+
+fn read_analytics() -> Result<Analytics> {
+    let path = get_analytics_path()?;
+    if !path.exists() {
+        return Ok(Analytics::default())
+    }
+    Ok(serde_json::from_reader(File::open(path)?)?)
+}
+
+fn write_analytics(analytics: &Analytics) -> Result<()> {
+    faults::inject(Fault::DiskWrite)?;
+    write_atomic(&get_analytics_path()?, &serde_json::to_vec_pretty(analytics)?)
+}
+
+pub fn record(session: Session, crashed: bool) -> Result<()> {
+    let played = session.started.elapsed();
+
+    let _lock = ANALYTICS_LOCK.lock().unwrap();
+    let mut analytics = read_analytics()?;
+    analytics.total.add(&session, played, crashed);
+    analytics.clients.entry(session.client.clone()).or_default().add(&session, played, crashed);
+    write_analytics(&analytics)
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn get_analytics() -> Result<Analytics> {
+    let _lock = ANALYTICS_LOCK.lock().unwrap();
+    read_analytics()
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn reset_analytics() -> Result<()> {
+    let _lock = ANALYTICS_LOCK.lock().unwrap();
+    write_analytics(&Analytics::default())
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn export_analytics(path: String) -> Result<()> {
+    let _lock = ANALYTICS_LOCK.lock().unwrap();
+    write_atomic(Path::new(&path), &serde_json::to_vec_pretty(&read_analytics()?)?)
+}
@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessRefreshKind, SystemExt};
+use tauri::{AppHandle, Manager};
+use tracing::{error, info};
+
+use crate::disk_space::ensure_free_space;
+use crate::error::Result;
+use crate::features::{self, Feature};
+use crate::notifications::{self, NotificationEvent};
+use crate::settings::{get_setting, set_setting};
+use crate::tasks::TaskOutcome;
+use crate::{launch_with_weave, mark_killed, minecraft_processes, AppState, ClientType, MinecraftProcess};
+
+// Watches for Minecraft processes started without Weave and relaunches them with it, for the
+// client types listed in the "auto_attach_clients" setting. Only games started within the
+// grace period are touched, anything older is left alone since the player may already be in
+// a world. Runs while the window is hidden to the tray, gated by the AutoAttach feature flag.
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const DEFAULT_GRACE_PERIOD_SECS: u64 = 20;
+const EXIT_TIMEOUT: Duration = Duration::from_secs(10);
+const TASK_NAME: &str = "auto_attach";
+
+// size of the watcher's seen set, for dump_state
+static SEEN_PROCESSES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Serialize)]
+pub struct AutoAttachClients {
+    // whether the AutoAttach feature flag is on, the watcher ignores the clients while it's off
+    available: bool,
+    clients: Vec<ClientType>
+}
+
+#[derive(Clone, Serialize)]
+struct AutoAttachPayload {
+    killed_pid: u32,
+    pid: u32,
+    client: ClientType
+}
+
+#[derive(Clone, Serialize)]
+struct AutoAttachFailedPayload {
+    killed_pid: u32,
+    // the game started again without Weave, None if that failed too
+    pid: Option<u32>,
+    client: ClientType,
+    error: String
+}
+
+fn enabled_clients() -> Vec<ClientType> {
+    get_setting("auto_attach_clients").unwrap_or_default()
+}
+
+fn started_within_grace_period(process: &MinecraftProcess) -> bool {
+    let grace_period = get_setting::<u64>("auto_attach_grace_secs").unwrap_or(DEFAULT_GRACE_PERIOD_SECS);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or_default();
+    now.saturating_sub(process.start_time) <= grace_period
+}
+
+// the relaunch reuses the game directory, which the old process holds a lock on until it exits
+fn kill_and_wait(app_state: &AppState, pid: u32) -> Result<()> {
+    mark_killed(app_state, pid);
+    let pid = Pid::from_u32(pid);
+    let killed = app_state.system.lock().unwrap().process(pid).is_some_and(|process| process.kill());
+    if !killed {
+        app_state.killed_processes.lock().unwrap().remove(&pid.as_u32());
+        Err(format!("Failed to stop process {}", pid))?;
+    }
+
+    let started = Instant::now();
+    while started.elapsed() < EXIT_TIMEOUT {
+        let mut system = app_state.system.lock().unwrap();
+        system.refresh_processes_specifics(ProcessRefreshKind::new());
+        if system.process(pid).is_none() {
+            return Ok(())
+        }
+        drop(system);
+        thread::sleep(Duration::from_millis(200));
+    }
+    Err(format!("Process {} did not exit in time", pid).into())
+}
+
+// starts the game again the way its launcher did, after the relaunch with Weave failed
+fn relaunch_without_weave(cmd: &[String], cwd: &str) -> Result<u32> {
+    let program = cmd.first().ok_or("Empty command line")?;
+    Ok(Command::new(program).args(&cmd[1..]).current_dir(cwd).spawn()?.id())
+}
+
+// a game restarted without Weave goes into `seen`, so the next poll doesn't stop it again
+fn attach(app: &AppHandle, process: MinecraftProcess, seen: &mut HashSet<u32>) -> Result<()> {
+    let app_state = app.state::<AppState>();
+    let client = process.info.client.clone();
+    let version = process.info.version.clone();
+
+    // what launch_with_weave checks first is checked before the game is stopped
+    app_state.dirs.loader()?;
+    ensure_free_space(&app_state.dirs, &app_state.dirs.client_logs()?, 0)?;
+
+    kill_and_wait(&app_state, process.pid)?;
+    let (cmd, cwd) = (process.info.cmd.clone(), process.info.cwd.clone());
+    let pid = match launch_with_weave(process.info, app.clone()) {
+        Ok(pid) => pid,
+        Err(e) => {
+            let relaunched = relaunch_without_weave(&cmd, &cwd);
+            if let Err(relaunch_error) = &relaunched {
+                error!("Failed to restart Minecraft {} without Weave: {}", version, relaunch_error);
+            }
+            let pid = relaunched.ok();
+            seen.extend(pid);
+
+            let _ = app.emit_all("auto_attach_failed", AutoAttachFailedPayload {
+                killed_pid: process.pid,
+                pid,
+                client,
+                error: e.to_string()
+            });
+            let body = match pid {
+                Some(pid) => format!("Minecraft {} was restarted without Weave (PID {}): {}", version, pid, e),
+                None => format!("Minecraft {} was stopped and could not be restarted: {}", version, e)
+            };
+            notifications::notify(app, NotificationEvent::AutoAttachFailed, Some(&process.game_dir),
+                "Weave failed to attach", &body);
+            return Err(e)
+        }
+    };
+    info!("Auto-attached Weave to Minecraft {} (PID {} relaunched as {})", version, process.pid, pid);
+
+    app.emit_all("auto_attached", AutoAttachPayload { killed_pid: process.pid, pid, client })?;
+    notifications::notify(app, NotificationEvent::WeaveAutoAttached, Some(&process.game_dir),
+        "Weave attached", &format!("Relaunched Minecraft {} with Weave (PID {})", version, pid));
+    Ok(())
+}
+
+pub fn seen_count() -> usize {
+    SEEN_PROCESSES.load(Ordering::Relaxed)
+}
+
+pub fn start_watcher(app: AppHandle) {
+    app.state::<AppState>().tasks.register(TASK_NAME);
+    thread::spawn(move || {
+        // every pid is looked at once, starting with whatever was already running
+        let mut seen: HashSet<u32> = minecraft_processes(&app.state::<AppState>()).iter().map(|process| process.pid).collect();
+        SEEN_PROCESSES.store(seen.len(), Ordering::Relaxed);
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            // games started meanwhile show up as new once enabled, the grace period skips old ones
+            let app_state = app.state::<AppState>();
+            if !features::is_enabled(Feature::AutoAttach) {
+                app_state.tasks.record(TASK_NAME, TaskOutcome::Disabled);
+                continue
+            }
+
+            let processes = minecraft_processes(&app_state);
+            seen.retain(|pid| processes.iter().any(|process| process.pid == *pid));
+            let new: Vec<MinecraftProcess> = processes.into_iter().filter(|process| seen.insert(process.pid)).collect();
+            SEEN_PROCESSES.store(seen.len(), Ordering::Relaxed);
+
+            // the last failed attach of this poll is the one reported
+            let mut outcome = TaskOutcome::Ok;
+            let clients = enabled_clients();
+            for process in new {
+                if process.weave_attached || !clients.contains(&process.info.client) || !started_within_grace_period(&process) {
+                    continue
+                }
+                if let Err(e) = attach(&app, process, &mut seen) {
+                    error!("Failed to auto-attach Weave: {}", e);
+                    outcome = TaskOutcome::Failed(e.to_string());
+                }
+            }
+            SEEN_PROCESSES.store(seen.len(), Ordering::Relaxed);
+            app_state.tasks.record(TASK_NAME, outcome);
+        }
+    });
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+pub fn get_auto_attach_clients() -> AutoAttachClients {
+    AutoAttachClients {
+        available: features::is_enabled(Feature::AutoAttach),
+        clients: enabled_clients()
+    }
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn set_auto_attach(client: ClientType, enabled: bool) -> Result<()> {
+    if enabled && !features::is_enabled(Feature::AutoAttach) {
+        Err("Auto-attach isn't available yet, turn on the auto_attach feature flag to try it")?;
+    }
+    let mut clients = enabled_clients();
+    clients.retain(|existing| *existing != client);
+    if enabled {
+        clients.push(client);
+    }
+    set_setting("auto_attach_clients", clients)
+}
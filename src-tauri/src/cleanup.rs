@@ -0,0 +1,245 @@
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use tauri::State;
+
+use crate::error::Result;
+use crate::faults::{self, Fault};
+use crate::paths::WeaveDirs;
+use crate::{instances, journal, logging, mod_updates, AppState};
+
+#[derive(Deserialize)]
+pub struct CleanupOptions {
+    #[serde(default)]
+    logs: bool,
+    // staged updates and previous mod versions updates.json no longer points at
+    #[serde(default)]
+    caches: bool,
+    #[serde(default)]
+    disabled_mods: bool,
+    // journal transactions from earlier runs that recovery couldn't finish
+    #[serde(default)]
+    quarantine: bool,
+    // JVM crash dumps and crash reports in the game directories the manager knows about
+    #[serde(default)]
+    crash_dumps: bool,
+    older_than_days: u64,
+    // report what would be removed without touching anything
+    #[serde(default)]
+    dry_run: bool
+}
+
+#[derive(Clone, Copy, Serialize)]
+pub enum CleanupCategory {
+    Logs,
+    Caches,
+    DisabledMods,
+    Quarantine,
+    CrashDumps
+}
+
+#[derive(Serialize)]
+pub struct CleanupEntry {
+    category: CleanupCategory,
+    files_removed: u32,
+    bytes_reclaimed: u64
+}
+
+#[derive(Serialize)]
+pub struct CleanupReport {
+    categories: Vec<CleanupEntry>,
+    total_bytes_reclaimed: u64,
+    dry_run: bool
+}
+
+impl CleanupEntry {
+    fn new(category: CleanupCategory) -> Self {
+        CleanupEntry {
+            category,
+            files_removed: 0,
+            bytes_reclaimed: 0
+        }
+    }
+}
+
+fn entry_size(path: &Path) -> Result<u64> {
+    let metadata = fs::symlink_metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok(metadata.len())
+    }
+
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        size += entry_size(&entry?.path())?;
+    }
+    Ok(size)
+}
+
+fn is_older_than(path: &Path, max_age: Duration) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age >= max_age)
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name().and_then(|name| name.to_str()).unwrap_or_default()
+}
+
+fn is_crash_dump(path: &Path) -> bool {
+    let name = file_name(path);
+    (name.starts_with("hs_err_pid") && name.ends_with(".log"))
+        || name.ends_with(".mdmp")
+        || name.ends_with(".hprof")
+}
+
+fn is_crash_report(path: &Path) -> bool {
+    path.is_file() && file_name(path).starts_with("crash-") && file_name(path).ends_with(".txt")
+}
+
+// games seen by the manager, from instances with a nickname and the launch history
+fn known_game_dirs(dirs: &WeaveDirs) -> BTreeSet<PathBuf> {
+    let read_json = |path: PathBuf| -> Option<Value> { serde_json::from_reader(File::open(path).ok()?).ok() };
+    let mut game_dirs = BTreeSet::new();
+
+    if let Some(Value::Object(nicknames)) = read_json(dirs.root().join("instances.json")) {
+        game_dirs.extend(nicknames.values().filter_map(|entry| entry["game_dir"].as_str()).map(PathBuf::from));
+    }
+
+    let history = dirs.history_logs().ok().and_then(|dir| read_json(dir.join("history.log")));
+    for process in history.as_ref().and_then(|history| history["history"].as_array()).into_iter().flatten() {
+        // entries written before game directories were tracked only have the command line
+        let game_dir = match process["game_dir"].as_str() {
+            Some(game_dir) => game_dir.to_string(),
+            None => {
+                let Some(cmd) = process["info"]["cmd"].as_array() else {
+                    continue
+                };
+                let cmd: Vec<String> = cmd.iter().filter_map(|arg| arg.as_str()).map(String::from).collect();
+                instances::game_dir(&cmd, process["info"]["cwd"].as_str().unwrap_or_default())
+            }
+        };
+        game_dirs.insert(PathBuf::from(game_dir));
+    }
+
+    game_dirs.retain(|game_dir| game_dir.is_dir());
+    game_dirs
+}
+
+// a disabled copy is orphaned when an enabled jar with the same name sits next to it,
+// since it can never be re-enabled without overwriting that jar
+fn is_orphaned_disabled_mod(path: &Path) -> bool {
+    match file_name(path).strip_suffix(".disabled") {
+        Some(jar) if jar.ends_with(".jar") => path.with_file_name(jar).exists(),
+        _ => false
+    }
+}
+
+// removes every direct child of `dir` accepted by `filter`, recording the reclaimed space
+fn sweep<F>(dirs: &WeaveDirs, dir: &Path, entry: &mut CleanupEntry, dry_run: bool, filter: F) -> Result<()>
+    where F: Fn(&Path) -> bool
+{
+    if !dir.exists() {
+        return Ok(())
+    }
+
+    for child in fs::read_dir(dir)? {
+        let path = child?.path();
+        if !filter(&path) {
+            continue
+        }
+
+        let size = entry_size(&path)?;
+        if !dry_run {
+            faults::inject_from(dirs, Fault::DiskWrite)?;
+            if path.is_dir() {
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+        }
+
+        entry.files_removed += 1;
+        entry.bytes_reclaimed += size;
+    }
+
+    Ok(())
+}
+
+pub fn run_cleanup(dirs: &WeaveDirs, options: CleanupOptions) -> Result<CleanupReport> {
+    let max_age = Duration::from_secs(options.older_than_days.saturating_mul(24 * 60 * 60));
+    let dry_run = options.dry_run;
+    let mut categories = Vec::new();
+
+    if options.logs {
+        let mut entry = CleanupEntry::new(CleanupCategory::Logs);
+        sweep(dirs, &dirs.client_logs()?, &mut entry, dry_run, |path| {
+            file_name(path).ends_with(".log") && is_older_than(path, max_age)
+        })?;
+        sweep(dirs, &dirs.manager_logs()?, &mut entry, dry_run, |path| {
+            logging::is_old_log_file(file_name(path)) && is_older_than(path, max_age)
+        })?;
+        categories.push(entry);
+    }
+
+    if options.caches {
+        let mut entry = CleanupEntry::new(CleanupCategory::Caches);
+        // downloads and launches change these folders and updates.json under the same lock
+        let _lock = mod_updates::UPDATES_LOCK.lock().unwrap();
+        let updates = mod_updates::read_updates(dirs)?;
+        sweep(dirs, &dirs.staged_updates()?, &mut entry, dry_run, |path| {
+            !updates.is_staged(file_name(path)) && is_older_than(path, max_age)
+        })?;
+        sweep(dirs, &dirs.previous_mods()?, &mut entry, dry_run, |path| {
+            !updates.has_previous(file_name(path)) && is_older_than(path, max_age)
+        })?;
+        categories.push(entry);
+    }
+
+    if options.disabled_mods {
+        let mut entry = CleanupEntry::new(CleanupCategory::DisabledMods);
+        sweep(dirs, &dirs.mods()?, &mut entry, dry_run, is_orphaned_disabled_mod)?;
+        categories.push(entry);
+    }
+
+    if options.quarantine {
+        let mut entry = CleanupEntry::new(CleanupCategory::Quarantine);
+        sweep(dirs, &dirs.journal()?, &mut entry, dry_run, |path| {
+            path.is_dir() && journal::is_leftover(path) && is_older_than(path, max_age)
+        })?;
+        categories.push(entry);
+    }
+
+    if options.crash_dumps {
+        let mut entry = CleanupEntry::new(CleanupCategory::CrashDumps);
+        // the JVM drops its dumps into the working directory, which is the game directory for
+        // every supported launcher, and Minecraft writes crash reports below it
+        for game_dir in known_game_dirs(dirs) {
+            sweep(dirs, &game_dir, &mut entry, dry_run, |path| {
+                path.is_file() && is_crash_dump(path) && is_older_than(path, max_age)
+            })?;
+            sweep(dirs, &game_dir.join("crash-reports"), &mut entry, dry_run, |path| {
+                is_crash_report(path) && is_older_than(path, max_age)
+            })?;
+        }
+        categories.push(entry);
+    }
+
+    let total_bytes_reclaimed = categories.iter().map(|entry| entry.bytes_reclaimed).sum();
+
+    Ok(CleanupReport {
+        categories,
+        total_bytes_reclaimed,
+        dry_run
+    })
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn cleanup(options: CleanupOptions, app_state: State<AppState>) -> Result<CleanupReport> {
+    run_cleanup(&app_state.dirs, options)
+}
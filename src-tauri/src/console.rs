@@ -0,0 +1,240 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::thread;
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+use tracing::error;
+
+use crate::error::Result;
+use crate::{overlay, AppState, ClientType, WeaveProcess};
+
+// replayed consoles count down from the top of the pid range to never collide with real processes
+static NEXT_FAKE_PID: AtomicU32 = AtomicU32::new(u32::MAX);
+
+// lines kept per pid for replaying a console when it is selected again
+const HISTORY_LINES: usize = 2000;
+
+#[derive(Clone, Copy, Serialize)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error
+}
+
+#[derive(Clone, Serialize)]
+pub struct ConsolePayload {
+    line: String,
+    pid: u32,
+    // nickname or version of the instance, for telling lines apart in the aggregated view
+    instance: Option<String>,
+    // increases by one per line across all instances, history and live lines share it
+    sequence: u64,
+    // None for lines without a level, e.g. stack traces and raw stdout prints
+    level: Option<LogLevel>
+}
+
+#[derive(Clone, Serialize)]
+struct ExitPayload {
+    pid: u32,
+    code: Option<i32>
+}
+
+#[derive(Default)]
+pub struct ConsoleState {
+    replays: Mutex<HashSet<u32>>,
+    // "all instances" mode, emits the output of every tracked pid instead of the selected one
+    aggregate: AtomicBool,
+    instances: Mutex<HashMap<u32, String>>,
+    history: Mutex<HashMap<u32, VecDeque<ConsolePayload>>>,
+    // held while emitting so lines from different instances reach the renderer in sequence order
+    sequence: Mutex<u64>
+}
+
+impl ConsoleState {
+    pub fn active_replays(&self) -> Vec<u32> {
+        self.replays.lock().unwrap().iter().copied().collect()
+    }
+
+    // console log and recording replays share the fake pid space and stop_console_replay
+    pub fn start_replay(&self) -> u32 {
+        let replay_id = next_fake_pid();
+        self.replays.lock().unwrap().insert(replay_id);
+        replay_id
+    }
+
+    pub fn is_replaying(&self, replay_id: u32) -> bool {
+        self.replays.lock().unwrap().contains(&replay_id)
+    }
+
+    pub fn finish_replay(&self, replay_id: u32) {
+        self.replays.lock().unwrap().remove(&replay_id);
+    }
+
+    // buffered lines per pid
+    pub fn history_sizes(&self) -> HashMap<u32, usize> {
+        self.history.lock().unwrap().iter().map(|(&pid, lines)| (pid, lines.len())).collect()
+    }
+
+    // names the pid in aggregated output until it exits
+    pub fn track_instance(&self, pid: u32, name: String) {
+        self.instances.lock().unwrap().insert(pid, name);
+    }
+}
+
+// the level from log4j's "[12:34:56] [Render thread/INFO]: " prefix, forge puts another
+// "[FML]" tag after it
+fn parse_level(line: &str) -> Option<LogLevel> {
+    let prefix = &line[..line.find("]: ")?];
+    prefix.split('[')
+        .filter_map(|tag| tag.split(']').next()?.rsplit_once('/'))
+        .find_map(|(_, level)| match level {
+            "DEBUG" | "TRACE" => Some(LogLevel::Debug),
+            "INFO" => Some(LogLevel::Info),
+            "WARN" => Some(LogLevel::Warn),
+            "ERROR" | "FATAL" => Some(LogLevel::Error),
+            _ => None
+        })
+}
+
+// every console line, whether it comes from a real process or a replay, goes through here
+pub fn emit_line(app: &AppHandle, pid: u32, line: String) {
+    let app_state = app.state::<AppState>();
+    overlay::inspect_line(&app_state.overlay, pid, &line);
+
+    let console = &app_state.console;
+    let instance = console.instances.lock().unwrap().get(&pid).cloned();
+    let mut sequence = console.sequence.lock().unwrap();
+    *sequence += 1;
+    let payload = ConsolePayload {
+        level: parse_level(&line),
+        line,
+        pid,
+        instance,
+        sequence: *sequence
+    };
+
+    // buffered for every pid, so output produced while another instance is selected isn't lost
+    let mut history = console.history.lock().unwrap();
+    let lines = history.entry(pid).or_default();
+    if lines.len() == HISTORY_LINES {
+        lines.pop_front();
+    }
+    lines.push_back(payload.clone());
+    drop(history);
+
+    if !console.aggregate.load(Ordering::Relaxed) && app_state.selected_process.load(Ordering::Relaxed) != pid {
+        return
+    }
+    app.emit_all("console_output", payload).expect("Failed to emit console_output event to renderer");
+}
+
+pub fn emit_exit(app: &AppHandle, pid: u32, code: Option<i32>) {
+    let console = &app.state::<AppState>().console;
+    console.instances.lock().unwrap().remove(&pid);
+    // the full output of an exited instance is in its log file
+    console.history.lock().unwrap().remove(&pid);
+
+    app.emit_all("weave_exited", ExitPayload {
+        pid,
+        code
+    }).expect("Failed to emit weave_exited event to renderer");
+}
+
+pub fn next_fake_pid() -> u32 {
+    NEXT_FAKE_PID.fetch_sub(1, Ordering::Relaxed)
+}
+
+// seconds since midnight from a "[HH:MM:SS]" line prefix
+fn parse_timestamp(line: &str) -> Option<u32> {
+    let time = line.strip_prefix('[')?.get(..9)?.strip_suffix(']')?;
+    let mut parts = time.split(':').map(|part| part.parse::<u32>().ok());
+    let (hours, minutes, seconds) = (parts.next()??, parts.next()??, parts.next()??);
+    Some(hours * 3600 + minutes * 60 + seconds)
+}
+
+fn replay(app: AppHandle, log_path: PathBuf, pid: u32, speed: f64) -> Result<()> {
+    let reader = BufReader::new(File::open(&log_path)?);
+
+    app.emit_all("spawned_weave", WeaveProcess {
+        log_file: log_path,
+        client: ClientType::Vanilla,
+        pid,
+        output: Vec::new()
+    })?;
+
+    let app_state = app.state::<AppState>();
+    app_state.console.track_instance(pid, "Replay".to_string());
+    let mut last_timestamp = None;
+    for line in reader.lines().filter_map(|l| l.ok()) {
+        if !app_state.console.is_replaying(pid) {
+            break
+        }
+
+        // keep the recorded pacing between lines, scaled by the requested speed
+        if let Some(timestamp) = parse_timestamp(&line) {
+            if let Some(last) = last_timestamp.filter(|_| speed > 0.0) {
+                let elapsed = if timestamp >= last { timestamp - last } else { timestamp + 86400 - last };
+                thread::sleep(Duration::from_secs_f64(elapsed as f64 / speed));
+            }
+            last_timestamp = Some(timestamp);
+        }
+
+        emit_line(&app, pid, line);
+    }
+
+    overlay::forget(&app_state.overlay, pid);
+    emit_exit(&app, pid, None);
+    Ok(())
+}
+
+// Developer tool: replays a recorded log through the console pipeline under a fake pid. A speed
+// of 2.0 plays twice as fast as recorded, 0 or less emits as fast as possible.
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn replay_console_log(path: String, speed: f64, app_state: State<AppState>, app: AppHandle) -> Result<u32> {
+    if !cfg!(debug_assertions) {
+        Err("Console replay is only available in development builds")?;
+    }
+
+    let pid = app_state.console.start_replay();
+    app_state.selected_process.store(pid, Ordering::Relaxed);
+
+    thread::spawn(move || {
+        if let Err(e) = replay(app.clone(), PathBuf::from(path), pid, speed) {
+            error!("Failed to replay console log: {}", e);
+        }
+        app.state::<AppState>().console.finish_replay(pid);
+    });
+
+    Ok(pid)
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+pub fn stop_console_replay(pid: u32, app_state: State<AppState>) {
+    app_state.console.finish_replay(pid);
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+pub fn set_console_aggregate(enabled: bool, app_state: State<AppState>) {
+    app_state.console.aggregate.store(enabled, Ordering::Relaxed);
+}
+
+// the last `max_lines` lines of the pid, oldest first. Lines emitted after this returns have a
+// higher sequence, so the renderer can drop live lines it already got from the history.
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+pub fn get_console_history(pid: u32, max_lines: usize, app_state: State<AppState>) -> Vec<ConsolePayload> {
+    let history = app_state.console.history.lock().unwrap();
+    let Some(lines) = history.get(&pid) else {
+        return Vec::new()
+    };
+    lines.iter().skip(lines.len().saturating_sub(max_lines)).cloned().collect()
+}
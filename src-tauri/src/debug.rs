@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use serde::Serialize;
+use sysinfo::SystemExt;
+use tauri::{AppHandle, Manager, State};
+
+use crate::tasks::TaskStatus;
+use crate::{auto_attach, overlay, AppState, WeaveProcess};
+
+// point-in-time view of the backend for the debug panel and bug reports
+#[derive(Serialize)]
+pub struct StateSnapshot {
+    manager_version: String,
+    selected_process: u32,
+    weave_processes: Vec<WeaveProcess>,
+    system_processes: usize,
+    mock_instances: bool,
+    recording: Option<PathBuf>,
+    console_replays: Vec<u32>,
+    console_history: HashMap<u32, usize>,
+    overlay_open: bool,
+    overlay_fps_entries: usize,
+    overlay_error_entries: usize,
+    notification_rules: usize,
+    background_tasks: Vec<TaskStatus>,
+    auto_attach_seen: usize
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+pub fn dump_state(app_state: State<AppState>, app: AppHandle) -> StateSnapshot {
+    let (overlay_fps_entries, overlay_error_entries) = app_state.overlay.buffer_sizes();
+
+    StateSnapshot {
+        manager_version: app.package_info().version.to_string(),
+        selected_process: app_state.selected_process.load(Ordering::Relaxed),
+        weave_processes: app_state.weave_processes.lock().unwrap().values().cloned().collect(),
+        system_processes: app_state.system.lock().unwrap().processes().len(),
+        mock_instances: app_state.mock.is_some(),
+        recording: app_state.recording.active_path(),
+        console_replays: app_state.console.active_replays(),
+        console_history: app_state.console.history_sizes(),
+        overlay_open: app.get_window(overlay::OVERLAY_LABEL).is_some(),
+        overlay_fps_entries,
+        overlay_error_entries,
+        notification_rules: app_state.notification_rules.lock().unwrap().rule_count(),
+        background_tasks: app_state.tasks.snapshot(),
+        auto_attach_seen: auto_attach::seen_count()
+    }
+}
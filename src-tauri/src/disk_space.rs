@@ -0,0 +1,48 @@
+use std::path::Path;
+use sysinfo::{DiskExt, System, SystemExt};
+use tracing::warn;
+
+use crate::error::Result;
+use crate::paths::WeaveDirs;
+use crate::settings::get_setting_from;
+
+// overridable with "min_free_space_mb" in manager.settings
+const DEFAULT_MIN_FREE_SPACE_MB: u64 = 500;
+
+const MB: u64 = 1024 * 1024;
+
+// free space on the volume `path` lives on, None if the volume could not be determined
+fn free_space(path: &Path) -> Option<(String, u64)> {
+    // the path itself may not exist yet, e.g. a log file about to be created
+    let path = path.ancestors().find_map(|ancestor| ancestor.canonicalize().ok())?;
+
+    let mut system = System::new();
+    system.refresh_disks_list();
+
+    // the most specific mount point wins, e.g. /home over /
+    system.disks().iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| (disk.mount_point().display().to_string(), disk.available_space()))
+}
+
+// Call before an operation that writes `bytes` (0 if unknown) below `path`. Fails while the
+// volume would be left with less than the configured minimum, and warns when it gets close.
+pub fn ensure_free_space(dirs: &WeaveDirs, path: &Path, bytes: u64) -> Result<()> {
+    let Some((mount_point, available)) = free_space(path) else {
+        return Ok(())
+    };
+
+    let min_free = get_setting_from::<u64>(dirs, "min_free_space_mb").unwrap_or(DEFAULT_MIN_FREE_SPACE_MB).saturating_mul(MB);
+    let remaining = available.saturating_sub(bytes);
+    if remaining < min_free {
+        Err(format!(
+            "Not enough disk space on {}: {} MB free, {} MB needed plus {} MB kept free. Free up some space and try again",
+            mount_point, available / MB, bytes.div_ceil(MB), min_free / MB
+        ))?;
+    }
+    if remaining < min_free.saturating_mul(2) {
+        warn!("Disk space on {} is running low ({} MB free)", mount_point, available / MB);
+    }
+    Ok(())
+}
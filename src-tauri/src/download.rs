@@ -0,0 +1,39 @@
+use std::io::Read;
+
+use crate::disk_space::ensure_free_space;
+use crate::error::Result;
+use crate::faults::{self, Fault};
+use crate::paths::WeaveDirs;
+
+pub const USER_AGENT: &str = "weave-manager";
+
+pub fn download(dirs: &WeaveDirs, url: &str) -> Result<Vec<u8>> {
+    download_with_progress(dirs, url, |_, _| {})
+}
+
+// `on_progress` gets the bytes received so far and the total size, if the server sent one
+pub fn download_with_progress<F>(dirs: &WeaveDirs, url: &str, mut on_progress: F) -> Result<Vec<u8>>
+    where F: FnMut(u64, Option<u64>)
+{
+    faults::inject_from(dirs, Fault::Download)?;
+
+    let response = ureq::get(url)
+        .set("User-Agent", USER_AGENT)
+        .call()?;
+
+    let length = response.header("Content-Length").and_then(|length| length.parse().ok());
+    ensure_free_space(dirs, dirs.root(), length.unwrap_or(0))?;
+
+    let mut reader = response.into_reader();
+    let mut bytes = Vec::with_capacity(length.unwrap_or(0) as usize);
+    let mut chunk = [0; 64 * 1024];
+    loop {
+        let read = reader.read(&mut chunk)?;
+        if read == 0 {
+            break
+        }
+        bytes.extend_from_slice(&chunk[..read]);
+        on_progress(bytes.len() as u64, length);
+    }
+    Ok(bytes)
+}
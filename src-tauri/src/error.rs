@@ -14,10 +14,22 @@ pub enum Error {
     #[error("IO Error: {0}")]
     IO(#[from] io::Error),
 
+    #[error("HTTP Error: {0}")]
+    Http(Box<ureq::Error>),
+
+    #[error("Tauri Error: {0}")]
+    Tauri(#[from] tauri::Error),
+
     #[error("{0}")]
     Message(String)
 }
 
+impl From<ureq::Error> for Error {
+    fn from(value: ureq::Error) -> Self {
+        Error::Http(Box::new(value))
+    }
+}
+
 impl From<&str> for Error {
     fn from(value: &str) -> Self {
         Error::Message(value.to_string())
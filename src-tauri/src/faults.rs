@@ -0,0 +1,62 @@
+use std::io;
+use std::thread;
+use std::time::Duration;
+use serde::Deserialize;
+use zip::result::ZipError;
+
+use crate::error::{Error, Result};
+use crate::paths::WeaveDirs;
+use crate::settings::get_setting_from;
+
+// Hidden "debug_faults" object in manager.settings, e.g. {"download_failure": true, "slow_disk_ms": 500}.
+// Lets error handling paths and their UI states be exercised on demand, ignored in release builds.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct FaultSettings {
+    download_failure: bool,
+    corrupt_zip: bool,
+    permission_error: bool,
+    slow_disk_ms: u64
+}
+
+#[derive(Clone, Copy)]
+pub enum Fault {
+    Download,
+    ZipRead,
+    DiskWrite
+}
+
+fn fault_settings(dirs: &WeaveDirs) -> FaultSettings {
+    if !cfg!(debug_assertions) {
+        return FaultSettings::default()
+    }
+    get_setting_from(dirs, "debug_faults").unwrap_or_default()
+}
+
+pub fn inject(fault: Fault) -> Result<()> {
+    inject_from(&WeaveDirs::from_home()?, fault)
+}
+
+// call before the operation a fault would hit, returns the simulated error if it is enabled
+pub fn inject_from(dirs: &WeaveDirs, fault: Fault) -> Result<()> {
+    let settings = fault_settings(dirs);
+
+    match fault {
+        Fault::Download if settings.download_failure => {
+            Err("Injected fault: download failed".into())
+        }
+        Fault::ZipRead if settings.corrupt_zip => {
+            Err(Error::Zip(ZipError::InvalidArchive("Injected fault: corrupt zip")))
+        }
+        Fault::DiskWrite => {
+            if settings.slow_disk_ms > 0 {
+                thread::sleep(Duration::from_millis(settings.slow_disk_ms));
+            }
+            if settings.permission_error {
+                return Err(io::Error::new(io::ErrorKind::PermissionDenied, "Injected fault: permission denied").into())
+            }
+            Ok(())
+        }
+        _ => Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::PathBuf;
+use serde::{Serialize, Deserialize};
+use tracing::{info, warn};
+
+use crate::download::download;
+use crate::error::Result;
+use crate::journal::write_atomic;
+use crate::paths::{get_weave_directory, WeaveDirs};
+use crate::settings::get_setting;
+
+// Risky subsystems ship disabled and check their flag at runtime. Later sources win:
+//   1. the defaults below
+//   2. remote overrides, fetched from the "feature_flags_url" setting and cached in
+//      ~/.weave/feature_flags.json so they survive offline starts
+//   3. local overrides in the "feature_flags" settings object, e.g. {"auto_attach": true}
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    AutoAttach
+}
+
+#[derive(Clone, Copy, Serialize)]
+pub enum FlagSource {
+    Default,
+    Remote,
+    Settings
+}
+
+#[derive(Serialize)]
+pub struct FeatureFlag {
+    feature: Feature,
+    enabled: bool,
+    source: FlagSource
+}
+
+type FlagOverrides = HashMap<Feature, bool>;
+
+const ALL_FEATURES: [Feature; 1] = [Feature::AutoAttach];
+
+impl Feature {
+    fn default_enabled(self) -> bool {
+        match self {
+            Feature::AutoAttach => false
+        }
+    }
+}
+
+fn get_remote_flags_path() -> Result<PathBuf> {
+    Ok(get_weave_directory()?.join("feature_flags.json"))
+}
+
+// unknown flag names are dropped rather than failing the whole override set, so older
+// builds keep working against newer remote configs
+fn parse_overrides(value: serde_json::Value) -> FlagOverrides {
+    let serde_json::Value::Object(flags) = value else {
+        return FlagOverrides::new()
+    };
+    flags.into_iter()
+        .filter_map(|(name, enabled)| {
+            let feature = serde_json::from_value(serde_json::Value::String(name)).ok()?;
+            Some((feature, enabled.as_bool()?))
+        })
+        .collect()
+}
+
+fn read_remote_overrides() -> FlagOverrides {
+    get_remote_flags_path().ok()
+        .and_then(|path| File::open(path).ok())
+        .and_then(|file| serde_json::from_reader(file).ok())
+        .map(parse_overrides)
+        .unwrap_or_default()
+}
+
+fn read_settings_overrides() -> FlagOverrides {
+    get_setting("feature_flags").map(parse_overrides).unwrap_or_default()
+}
+
+fn resolve(feature: Feature, remote: &FlagOverrides, settings: &FlagOverrides) -> FeatureFlag {
+    let (enabled, source) = match (settings.get(&feature), remote.get(&feature)) {
+        (Some(&enabled), _) => (enabled, FlagSource::Settings),
+        (None, Some(&enabled)) => (enabled, FlagSource::Remote),
+        (None, None) => (feature.default_enabled(), FlagSource::Default)
+    };
+    FeatureFlag { feature, enabled, source }
+}
+
+// flags are re-read on every lookup so toggling one in settings applies without a restart
+pub fn is_enabled(feature: Feature) -> bool {
+    resolve(feature, &read_remote_overrides(), &read_settings_overrides()).enabled
+}
+
+// refreshes the cached remote overrides, keeps the previous cache if the fetch fails
+fn refresh_remote() -> Result<()> {
+    let Some(url) = get_setting::<String>("feature_flags_url").filter(|url| !url.is_empty()) else {
+        return Ok(())
+    };
+
+    let bytes = download(&WeaveDirs::from_home()?, &url)?;
+    let overrides = parse_overrides(serde_json::from_slice(&bytes)?);
+    write_atomic(&get_remote_flags_path()?, &serde_json::to_vec_pretty(&overrides)?)?;
+    info!("Fetched {} remote feature flag overrides", overrides.len());
+    Ok(())
+}
+
+pub fn refresh_remote_in_background() {
+    std::thread::spawn(|| {
+        if let Err(e) = refresh_remote() {
+            warn!("Failed to refresh remote feature flags: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+pub fn get_feature_flags() -> Vec<FeatureFlag> {
+    let remote = read_remote_overrides();
+    let settings = read_settings_overrides();
+    ALL_FEATURES.iter().map(|&feature| resolve(feature, &remote, &settings)).collect()
+}
@@ -0,0 +1,175 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use serde::{Serialize, Deserialize};
+use tauri::AppHandle;
+
+use crate::error::Result;
+use crate::faults::{self, Fault};
+use crate::journal::write_atomic;
+use crate::paths::get_weave_profiles_path;
+use crate::{launch_with_weave, ClientType, MinecraftInfo};
+
+// Game profiles start Minecraft from scratch instead of relaunching a copy of a running game's
+// command line like launch profiles (.lprof) do. They share ~/.weave/profiles with launch and
+// mod profiles under their own extension, and go through the same launch and console pipeline.
+
+const PROFILE_EXTENSION: &str = "gprof";
+const CLASSPATH_SEPARATOR: &str = if cfg!(windows) { ";" } else { ":" };
+
+#[derive(Serialize, Deserialize)]
+pub struct GameProfile {
+    name: String,
+    client: ClientType,
+    version: String,
+    java_path: String,
+    working_dir: String,
+    // -Xmx and -Xms, e.g. "4G" or "512M"
+    #[serde(default)]
+    max_memory: Option<String>,
+    #[serde(default)]
+    min_memory: Option<String>,
+    #[serde(default)]
+    jvm_args: Vec<String>,
+    // loaded after the Weave agent, "path" or "path=options"
+    #[serde(default)]
+    java_agents: Vec<String>,
+    #[serde(default)]
+    classpath: Vec<String>,
+    main_class: String,
+    #[serde(default)]
+    game_args: Vec<String>
+}
+
+impl GameProfile {
+    fn validate(&self) -> Result<()> {
+        if !Path::new(&self.java_path).is_file() {
+            Err(format!("Java executable ({}) not found", self.java_path))?;
+        }
+        if !Path::new(&self.working_dir).is_dir() {
+            Err(format!("Working directory ({}) not found", self.working_dir))?;
+        }
+        for memory in self.max_memory.iter().chain(&self.min_memory) {
+            let amount = memory.strip_suffix(['K', 'k', 'M', 'm', 'G', 'g']).unwrap_or(memory);
+            if amount.is_empty() || !amount.chars().all(|c| c.is_ascii_digit()) {
+                Err(format!("Invalid memory amount \"{}\", expected e.g. 4G or 512M", memory))?;
+            }
+        }
+        for agent in &self.java_agents {
+            let path = agent.split_once('=').map_or(agent.as_str(), |(path, _)| path);
+            if !Path::new(path).is_file() {
+                Err(format!("Java agent ({}) not found", path))?;
+            }
+        }
+        if self.main_class.is_empty() {
+            Err("A main class is required to start the game")?;
+        }
+        Ok(())
+    }
+
+    // the Weave agent is inserted right after the java path by launch_with_weave
+    fn command_line(&self) -> Vec<String> {
+        let mut cmd = vec![self.java_path.clone()];
+        if let Some(max_memory) = &self.max_memory {
+            cmd.push(format!("-Xmx{}", max_memory));
+        }
+        if let Some(min_memory) = &self.min_memory {
+            cmd.push(format!("-Xms{}", min_memory));
+        }
+        cmd.extend(self.jvm_args.iter().cloned());
+        cmd.extend(self.java_agents.iter().map(|agent| format!("-javaagent:{}", agent)));
+        if !self.classpath.is_empty() {
+            cmd.push("-cp".to_string());
+            cmd.push(self.classpath.join(CLASSPATH_SEPARATOR));
+        }
+        cmd.push(self.main_class.clone());
+        cmd.extend(self.game_args.iter().cloned());
+        cmd
+    }
+}
+
+fn profile_path(name: &str) -> Result<PathBuf> {
+    if name.trim().is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_') {
+        Err("Profile names may only contain letters, digits, spaces, '-' and '_'")?;
+    }
+    Ok(get_weave_profiles_path()?.join(format!("{}.{}", name, PROFILE_EXTENSION)))
+}
+
+fn read_profile(name: &str) -> Result<GameProfile> {
+    let path = profile_path(name)?;
+    if !path.exists() {
+        Err(format!("Profile {} not found", name))?;
+    }
+    Ok(serde_json::from_reader(File::open(path)?)?)
+}
+
+fn write_profile(profile: &GameProfile) -> Result<()> {
+    profile.validate()?;
+    let path = profile_path(&profile.name)?;
+
+    faults::inject(Fault::DiskWrite)?;
+    write_atomic(&path, &serde_json::to_vec_pretty(profile)?)
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn get_game_profiles() -> Result<Vec<GameProfile>> {
+    let mut profiles = Vec::new();
+    for entry in fs::read_dir(get_weave_profiles_path()?)? {
+        let path = entry?.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some(PROFILE_EXTENSION) {
+            continue
+        }
+        // a hand-edited profile that no longer parses shouldn't hide the others
+        if let Ok(Ok(profile)) = File::open(&path).map(serde_json::from_reader::<_, GameProfile>) {
+            profiles.push(profile);
+        }
+    }
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn create_game_profile(profile: GameProfile) -> Result<()> {
+    if profile_path(&profile.name)?.exists() {
+        Err(format!("A profile named {} already exists", profile.name))?;
+    }
+    write_profile(&profile)
+}
+
+// renaming is a delete and a create, the name identifies the file
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn update_game_profile(profile: GameProfile) -> Result<()> {
+    read_profile(&profile.name)?;
+    write_profile(&profile)
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn delete_game_profile(name: String) -> Result<()> {
+    let path = profile_path(&name)?;
+    if !path.exists() {
+        Err(format!("Profile {} not found", name))?;
+    }
+    faults::inject(Fault::DiskWrite)?;
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+// starts the profile with Weave and returns the new pid, its output shows up in the console
+// like any other launch
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn launch_profile(name: String, app: AppHandle) -> Result<u32> {
+    let profile = read_profile(&name)?;
+    // checked again, the java install or game directory may have moved since it was saved
+    profile.validate()?;
+
+    launch_with_weave(MinecraftInfo {
+        cmd: profile.command_line(),
+        client: profile.client,
+        version: profile.version,
+        cwd: profile.working_dir
+    }, app)
+}
@@ -0,0 +1,53 @@
+use serde::Deserialize;
+
+use crate::download::USER_AGENT;
+use crate::error::Result;
+use crate::faults::{self, Fault};
+use crate::paths::WeaveDirs;
+
+const API_URL: &str = "https://api.github.com";
+
+#[derive(Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>
+}
+
+#[derive(Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    // "sha256:<hex>", only set for assets uploaded after GitHub started computing digests
+    #[serde(default)]
+    pub digest: Option<String>
+}
+
+impl ReleaseAsset {
+    pub fn sha256(&self) -> Option<&str> {
+        self.digest.as_deref()?.strip_prefix("sha256:")
+    }
+}
+
+fn fetch_release(dirs: &WeaveDirs, repo: &str, release: &str) -> Result<Release> {
+    if repo.split('/').count() != 2 || repo.split('/').any(str::is_empty) {
+        Err(format!("Invalid GitHub repository \"{}\", expected owner/name", repo))?;
+    }
+    faults::inject_from(dirs, Fault::Download)?;
+
+    let release = ureq::get(&format!("{}/repos/{}/releases/{}", API_URL, repo, release))
+        .set("User-Agent", USER_AGENT)
+        .set("Accept", "application/vnd.github+json")
+        .call()?
+        .into_json()?;
+    Ok(release)
+}
+
+// `repo` is "owner/name"
+pub fn latest_release(dirs: &WeaveDirs, repo: &str) -> Result<Release> {
+    fetch_release(dirs, repo, "latest")
+}
+
+pub fn release_by_tag(dirs: &WeaveDirs, repo: &str, tag: &str) -> Result<Release> {
+    fetch_release(dirs, repo, &format!("tags/{}", tag))
+}
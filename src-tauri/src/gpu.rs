@@ -0,0 +1,282 @@
+use std::process::Command;
+use std::sync::OnceLock;
+use serde::Serialize;
+use sysinfo::{Pid, PidExt, ProcessExt, SystemExt};
+use tauri::State;
+
+use crate::error::Result;
+use crate::settings::get_setting;
+use crate::AppState;
+
+// detection shells out and takes a while, the hardware does not change while we run
+static GPUS: OnceLock<Vec<Gpu>> = OnceLock::new();
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Intel,
+    Apple,
+    Other
+}
+
+#[derive(Clone, Serialize)]
+pub struct Gpu {
+    name: String,
+    vendor: GpuVendor,
+    integrated: bool
+}
+
+#[derive(Serialize)]
+pub struct GpuWarning {
+    message: String,
+    guidance: String,
+    // whether launching through the manager with "gpu_fix_on_launch" enabled fixes it
+    fix_available: bool
+}
+
+#[derive(Serialize)]
+pub struct GpuReport {
+    gpus: Vec<Gpu>,
+    hybrid: bool,
+    warning: Option<GpuWarning>
+}
+
+// lspci names APUs by codename, Windows by model, e.g. "Rembrandt [Radeon 680M]" and
+// "AMD Radeon 780M Graphics"
+const AMD_APU_MARKERS: [&str; 25] = [
+    "graphics", "vega",
+    "raven", "picasso", "renoir", "lucienne", "cezanne", "barcelo", "rembrandt", "mendocino",
+    "van gogh", "vangogh", "raphael", "phoenix", "hawk point", "strix", "krackan",
+    "610m", "660m", "680m", "740m", "760m", "780m", "880m", "890m"
+];
+
+fn has_discrete_model(lower: &str) -> bool {
+    lower.contains(" rx") || lower.contains(" pro") || lower.contains("arc")
+}
+
+impl Gpu {
+    fn new(name: String) -> Self {
+        let lower = name.to_lowercase();
+        let vendor = if lower.contains("nvidia") || lower.contains("geforce") || lower.contains("quadro") {
+            GpuVendor::Nvidia
+        } else if lower.contains("amd") || lower.contains("radeon") || lower.contains("ati ") {
+            GpuVendor::Amd
+        } else if lower.contains("intel") {
+            GpuVendor::Intel
+        } else if lower.contains("apple") {
+            GpuVendor::Apple
+        } else {
+            GpuVendor::Other
+        };
+
+        // best effort from the name: Intel is integrated unless it is an Arc card, AMD APUs report
+        // themselves as "Radeon Graphics", "Vega", a codename or a three digit "M" model
+        let integrated = match vendor {
+            GpuVendor::Intel => !lower.contains("arc"),
+            GpuVendor::Amd => !has_discrete_model(&lower) && AMD_APU_MARKERS.iter().any(|marker| lower.contains(marker)),
+            GpuVendor::Apple => true,
+            _ => false
+        };
+
+        Gpu { name, vendor, integrated }
+    }
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None
+    }
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// `lspci -mm -D` prints one device per line as quoted fields: slot "class" "vendor" "device" ...
+// Codenames change with every APU generation, so the PCI data is used where it is clearer
+// than the name. A render-only "3D controller" is always a discrete card. With several GPUs,
+// the one the firmware booted on (boot_vga) is the integrated one unless its name says
+// otherwise, which is how laptops and desktops with an iGPU come up.
+#[cfg(target_os = "linux")]
+fn query_gpus() -> Vec<Gpu> {
+    let Some(output) = command_output("lspci", &["-mm", "-D"]) else {
+        return Vec::new()
+    };
+    let devices: Vec<(&str, &str, String)> = output.lines()
+        .filter_map(|line| {
+            let slot = line.split_whitespace().next()?;
+            let fields: Vec<&str> = line.split('"').skip(1).step_by(2).collect();
+            let class = *fields.first()?;
+            if !["VGA compatible controller", "3D controller", "Display controller"].contains(&class) {
+                return None
+            }
+            Some((slot, class, format!("{} {}", fields.get(1)?, fields.get(2)?)))
+        })
+        .collect();
+
+    let several = devices.len() > 1;
+    devices.into_iter()
+        .map(|(slot, class, name)| {
+            let mut gpu = Gpu::new(name);
+            if class == "3D controller" {
+                gpu.integrated = false;
+            } else if several && !gpu.integrated && matches!(gpu.vendor, GpuVendor::Amd | GpuVendor::Intel)
+                && !has_discrete_model(&gpu.name.to_lowercase()) && boot_vga(slot) {
+                gpu.integrated = true;
+            }
+            gpu
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn boot_vga(slot: &str) -> bool {
+    std::fs::read_to_string(format!("/sys/bus/pci/devices/{}/boot_vga", slot)).is_ok_and(|boot_vga| boot_vga.trim() == "1")
+}
+
+#[cfg(windows)]
+fn query_gpus() -> Vec<Gpu> {
+    let output = command_output("powershell", &["-NoProfile", "-Command", "Get-CimInstance Win32_VideoController | Select-Object -ExpandProperty Name"])
+        .or_else(|| command_output("wmic", &["path", "win32_VideoController", "get", "name"]).map(|output| {
+            // drop the "Name" header
+            output.lines().skip(1).collect::<Vec<_>>().join("\n")
+        }));
+    output.unwrap_or_default().lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| Gpu::new(name.to_string()))
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn query_gpus() -> Vec<Gpu> {
+    let displays = command_output("system_profiler", &["SPDisplaysDataType", "-json"])
+        .and_then(|output| serde_json::from_str::<serde_json::Value>(&output).ok());
+    displays.as_ref()
+        .and_then(|displays| displays["SPDisplaysDataType"].as_array())
+        .map(|gpus| gpus.iter().filter_map(|gpu| gpu["sppci_model"].as_str().map(|name| Gpu::new(name.to_string()))).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn query_gpus() -> Vec<Gpu> {
+    Vec::new()
+}
+
+pub fn detect_gpus() -> &'static [Gpu] {
+    GPUS.get_or_init(query_gpus)
+}
+
+fn is_hybrid(gpus: &[Gpu]) -> bool {
+    gpus.iter().any(|gpu| gpu.integrated) && gpus.iter().any(|gpu| !gpu.integrated)
+}
+
+fn discrete_gpu(gpus: &[Gpu]) -> Option<&Gpu> {
+    gpus.iter().find(|gpu| !gpu.integrated)
+}
+
+// PRIME render offload is opt-in per process, so without these the game renders on the iGPU
+#[cfg(target_os = "linux")]
+fn offload_env(gpu: &Gpu) -> Vec<(&'static str, &'static str)> {
+    match gpu.vendor {
+        GpuVendor::Nvidia => vec![("__NV_PRIME_RENDER_OFFLOAD", "1"), ("__GLX_VENDOR_LIBRARY_NAME", "nvidia")],
+        _ => vec![("DRI_PRIME", "1")]
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn check_process(pid: u32, _exe: &str, gpus: &[Gpu]) -> Option<GpuWarning> {
+    let discrete = discrete_gpu(gpus)?;
+    let environ = std::fs::read(format!("/proc/{}/environ", pid)).ok()?;
+    let environ = String::from_utf8_lossy(&environ);
+    let offloaded = environ.split('\0')
+        .any(|var| offload_env(discrete).iter().any(|(key, value)| var == format!("{}={}", key, value)));
+    if offloaded {
+        return None
+    }
+
+    let env = offload_env(discrete).iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>().join(" ");
+    Some(GpuWarning {
+        message: format!("Minecraft is probably running on the integrated GPU instead of the {}", discrete.name),
+        guidance: format!("Start the launcher with {} set, or use prime-run if your distribution provides it", env),
+        fix_available: true
+    })
+}
+
+// Windows keeps per-executable GPU preferences in the registry, 2 is "high performance"
+#[cfg(windows)]
+const GPU_PREFERENCES_KEY: &str = r"HKCU\Software\Microsoft\DirectX\UserGpuPreferences";
+
+#[cfg(windows)]
+fn check_process(_pid: u32, exe: &str, gpus: &[Gpu]) -> Option<GpuWarning> {
+    let discrete = discrete_gpu(gpus)?;
+    let preference = command_output("reg", &["query", GPU_PREFERENCES_KEY, "/v", exe]).unwrap_or_default();
+    if preference.contains("GpuPreference=2;") {
+        return None
+    }
+
+    Some(GpuWarning {
+        message: format!("Java is not set to use the {}, Minecraft may run on the integrated GPU", discrete.name),
+        guidance: format!("Open Settings > System > Display > Graphics, add {} and set it to High performance", exe),
+        fix_available: true
+    })
+}
+
+// macOS switches GPUs automatically
+#[cfg(not(any(target_os = "linux", windows)))]
+fn check_process(_pid: u32, _exe: &str, _gpus: &[Gpu]) -> Option<GpuWarning> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn apply_fix(command: &mut Command, _java: &str, discrete: &Gpu) {
+    command.envs(offload_env(discrete));
+}
+
+#[cfg(windows)]
+fn apply_fix(_command: &mut Command, java: &str, _discrete: &Gpu) {
+    let status = Command::new("reg")
+        .args(["add", GPU_PREFERENCES_KEY, "/v", java, "/t", "REG_SZ", "/d", "GpuPreference=2;", "/f"])
+        .status();
+    if !status.is_ok_and(|status| status.success()) {
+        tracing::warn!("Failed to set the GPU preference for {}", java);
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn apply_fix(_command: &mut Command, _java: &str, _discrete: &Gpu) {}
+
+// applied to the relaunched game when the "gpu_fix_on_launch" setting is enabled
+pub fn apply_launch_fix(command: &mut Command, java: &str) {
+    let gpus = detect_gpus();
+    if !get_setting::<bool>("gpu_fix_on_launch").unwrap_or(false) || !is_hybrid(gpus) {
+        return
+    }
+    if let Some(discrete) = discrete_gpu(gpus) {
+        apply_fix(command, java, discrete);
+    }
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn get_gpu_info(pid: Option<u32>, app_state: State<AppState>) -> Result<GpuReport> {
+    let gpus = detect_gpus();
+    let hybrid = is_hybrid(gpus);
+
+    let warning = match pid {
+        Some(pid) if hybrid => {
+            let mut system = app_state.system.lock().unwrap();
+            if !system.refresh_process(Pid::from_u32(pid)) {
+                Err(format!("Process {} is not running", pid))?;
+            }
+            let exe = system.process(Pid::from_u32(pid)).map(|process| process.exe().to_string_lossy().to_string()).unwrap_or_default();
+            check_process(pid, &exe, gpus)
+        }
+        _ => None
+    };
+
+    Ok(GpuReport {
+        gpus: gpus.to_vec(),
+        hybrid,
+        warning
+    })
+}
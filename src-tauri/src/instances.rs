@@ -0,0 +1,118 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use data_encoding::HEXUPPER;
+use ring::digest::{digest, SHA256};
+use serde::{Serialize, Deserialize};
+
+use crate::error::Result;
+use crate::faults::{self, Fault};
+use crate::journal::write_atomic;
+use crate::paths::get_weave_directory;
+use crate::{sha256_hex, MinecraftProcess};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InstanceNickname {
+    game_dir: String,
+    nickname: String
+}
+
+#[derive(Serialize)]
+pub struct InstanceGroup {
+    game_dir: String,
+    nickname: Option<String>,
+    instances: Vec<MinecraftProcess>
+}
+
+fn get_instance_nicknames_path() -> Result<PathBuf> {
+    Ok(get_weave_directory()?.join("instances.json"))
+}
+
+// the game directory is taken from --gameDir and falls back to the working directory,
+// which is what the vanilla launcher does when the argument is missing. A relative
+// --gameDir is relative to the working directory of the game, not of the manager.
+pub fn game_dir(cmd: &[String], cwd: &str) -> String {
+    let game_dir = cmd.iter()
+        .skip_while(|&arg| arg != "--gameDir")
+        .nth(1)
+        .map(|game_dir| Path::new(cwd).join(game_dir))
+        .unwrap_or_else(|| PathBuf::from(cwd));
+
+    normalize_game_dir(&game_dir.to_string_lossy())
+}
+
+// resolves links and relative parts, so the same directory always compares and hashes equal
+pub fn normalize_game_dir(game_dir: &str) -> String {
+    fs::canonicalize(game_dir)
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| game_dir.to_string())
+}
+
+pub fn game_dir_hash(game_dir: &str) -> String {
+    HEXUPPER.encode(digest(&SHA256, game_dir.as_bytes()).as_ref())
+}
+
+// the same for every refresh while the process runs, but different for each launch of the
+// same game directory, unlike pids which the OS reuses
+pub fn instance_id(game_dir: &str, start_time: u64) -> String {
+    let hash = sha256_hex(format!("{}\n{}", game_dir, start_time).as_bytes()).unwrap_or_default();
+    hash.get(..16).unwrap_or_default().to_lowercase()
+}
+
+// instances sharing a game directory share mods, options and worlds, so they are shown together
+pub fn group_by_game_dir(processes: Vec<MinecraftProcess>) -> Vec<InstanceGroup> {
+    let mut groups: BTreeMap<String, InstanceGroup> = BTreeMap::new();
+    for process in processes {
+        let group = groups.entry(process.game_dir.clone()).or_insert_with(|| InstanceGroup {
+            game_dir: process.game_dir.clone(),
+            nickname: process.nickname.clone(),
+            instances: Vec::new()
+        });
+        group.instances.push(process);
+    }
+
+    let mut groups: Vec<InstanceGroup> = groups.into_values().collect();
+    for group in &mut groups {
+        group.instances.sort_by_key(|process| process.start_time);
+    }
+    groups
+}
+
+// nicknames are keyed by the hash of their game directory
+pub fn read_nicknames() -> Result<HashMap<String, InstanceNickname>> {
+    let nicknames_path = get_instance_nicknames_path()?;
+    if !nicknames_path.exists() {
+        return Ok(HashMap::new())
+    }
+    Ok(serde_json::from_reader(File::open(nicknames_path)?)?)
+}
+
+pub fn nickname_for(nicknames: &HashMap<String, InstanceNickname>, game_dir: &str) -> Option<String> {
+    nicknames.get(&game_dir_hash(game_dir)).map(|entry| entry.nickname.clone())
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn get_instance_nicknames() -> Result<Vec<InstanceNickname>> {
+    Ok(read_nicknames()?.into_values().collect())
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn set_instance_nickname(game_dir: String, nickname: Option<String>) -> Result<()> {
+    let mut nicknames = read_nicknames()?;
+    let game_dir = normalize_game_dir(&game_dir);
+    let hash = game_dir_hash(&game_dir);
+
+    match nickname.map(|name| name.trim().to_string()).filter(|name| !name.is_empty()) {
+        Some(nickname) => {
+            nicknames.insert(hash, InstanceNickname { game_dir, nickname });
+        }
+        None => {
+            nicknames.remove(&hash);
+        }
+    }
+
+    faults::inject(Fault::DiskWrite)?;
+    write_atomic(&get_instance_nicknames_path()?, &serde_json::to_vec(&nicknames)?)
+}
@@ -0,0 +1,262 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use chrono::prelude::Local;
+use serde::{Serialize, Deserialize};
+use tracing::{error, info, warn};
+
+use crate::disk_space::ensure_free_space;
+use crate::error::Result;
+use crate::paths::WeaveDirs;
+
+// Write-ahead journal for operations that touch several files in .weave.
+//
+// Every operation gets its own directory in ~/.weave/journal holding journal.json plus the
+// staged contents of every file it will write. Nothing outside the journal is touched while
+// preparing. On commit each step moves the original aside into the journal as a backup and
+// the staged file into place. Both are renames, so whether a step ran can be told from which
+// files still exist, and an interrupted commit is resumed (or rolled back) on next start.
+
+const MANIFEST_NAME: &str = "journal.json";
+
+static NEXT_JOURNAL_ID: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum JournalState {
+    // staging files, nothing outside the journal has been changed yet
+    Preparing,
+    // applying steps, resumed if interrupted
+    Committing,
+    // undoing steps after a failed commit, continued if interrupted
+    RollingBack
+}
+
+#[derive(Serialize, Deserialize)]
+enum JournalStep {
+    Write { target: PathBuf },
+    Remove { target: PathBuf }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    operation: String,
+    started_at: String,
+    state: JournalState,
+    steps: Vec<JournalStep>
+}
+
+pub struct Journal {
+    dirs: WeaveDirs,
+    dir: PathBuf,
+    manifest: Manifest
+}
+
+impl JournalStep {
+    fn target(&self) -> &Path {
+        match self {
+            JournalStep::Write { target } | JournalStep::Remove { target } => target
+        }
+    }
+}
+
+fn staged_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("staged-{}", index))
+}
+
+fn backup_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("backup-{}", index))
+}
+
+// safe to run again after an interruption at any point
+fn apply_step(dir: &Path, index: usize, step: &JournalStep) -> Result<()> {
+    let backup = backup_path(dir, index);
+    let target = step.target();
+
+    match step {
+        JournalStep::Write { .. } => {
+            let staged = staged_path(dir, index);
+            if !staged.exists() {
+                return Ok(())
+            }
+            if target.exists() && !backup.exists() {
+                fs::rename(target, &backup)?;
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&staged, target)?;
+        }
+        JournalStep::Remove { .. } => {
+            if target.exists() && !backup.exists() {
+                fs::rename(target, &backup)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// safe to run again after an interruption at any point
+fn undo_step(dir: &Path, index: usize, step: &JournalStep) -> Result<()> {
+    let backup = backup_path(dir, index);
+    let target = step.target();
+
+    // a write that already moved its staged file into place has to be taken out again
+    if let JournalStep::Write { .. } = step {
+        if !staged_path(dir, index).exists() && target.exists() {
+            fs::remove_file(target)?;
+        }
+    }
+    if backup.exists() {
+        fs::rename(&backup, target)?;
+    }
+    Ok(())
+}
+
+fn write_manifest(dir: &Path, manifest: &Manifest) -> Result<()> {
+    write_atomic(&dir.join(MANIFEST_NAME), &serde_json::to_vec_pretty(manifest)?)
+}
+
+// Single file writes don't need a journal. The contents go to "<name>.part" next to the target
+// and replace it with a rename, so readers and a crash mid-write see the old or the new file,
+// never a truncated one.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    fs::write(&part, contents)?;
+    fs::rename(&part, path)?;
+    Ok(())
+}
+
+fn rollback(dir: &Path, manifest: &mut Manifest) -> Result<()> {
+    manifest.state = JournalState::RollingBack;
+    write_manifest(dir, manifest)?;
+    for (index, step) in manifest.steps.iter().enumerate().rev() {
+        undo_step(dir, index, step)?;
+    }
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+impl Journal {
+    pub fn begin(dirs: &WeaveDirs, operation: &str) -> Result<Journal> {
+        let id = format!(
+            "{}-{}-{}",
+            Local::now().format("%Y%m%d-%H%M%S%3f"),
+            std::process::id(),
+            NEXT_JOURNAL_ID.fetch_add(1, Ordering::Relaxed)
+        );
+        let dir = dirs.journal()?.join(id);
+        fs::create_dir_all(&dir)?;
+
+        let manifest = Manifest {
+            operation: operation.to_string(),
+            started_at: Local::now().to_rfc3339(),
+            state: JournalState::Preparing,
+            steps: Vec::new()
+        };
+        write_manifest(&dir, &manifest)?;
+        Ok(Journal { dirs: dirs.clone(), dir, manifest })
+    }
+
+    // the step is only recorded once its contents are fully staged, so recovery never
+    // mistakes a half written staged file for one that was already moved into place
+    pub fn stage_write(&mut self, target: PathBuf, contents: &[u8]) -> Result<()> {
+        ensure_free_space(&self.dirs, &self.dir, contents.len() as u64)?;
+        fs::write(staged_path(&self.dir, self.manifest.steps.len()), contents)?;
+        self.manifest.steps.push(JournalStep::Write { target });
+        write_manifest(&self.dir, &self.manifest)
+    }
+
+    pub fn stage_remove(&mut self, target: PathBuf) -> Result<()> {
+        self.manifest.steps.push(JournalStep::Remove { target });
+        write_manifest(&self.dir, &self.manifest)
+    }
+
+    // applies every staged step, rolling back the ones already applied if any of them fails
+    pub fn commit(mut self) -> Result<()> {
+        self.manifest.state = JournalState::Committing;
+        write_manifest(&self.dir, &self.manifest)?;
+
+        let applied = self.manifest.steps.iter().enumerate()
+            .try_for_each(|(index, step)| apply_step(&self.dir, index, step));
+
+        match applied {
+            Ok(()) => fs::remove_dir_all(&self.dir)?,
+            Err(e) => {
+                if let Err(rollback_error) = rollback(&self.dir, &mut self.manifest) {
+                    error!("Failed to roll back {}, retrying on next start: {}", self.manifest.operation, rollback_error);
+                }
+                return Err(e)
+            }
+        }
+        Ok(())
+    }
+}
+
+// an operation that failed or was dropped before committing never touched anything
+// outside its journal, so the journal can simply be thrown away
+impl Drop for Journal {
+    fn drop(&mut self) {
+        if self.manifest.state == JournalState::Preparing && self.dir.exists() {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+}
+
+fn recover_journal(dir: &Path) -> Result<()> {
+    let manifest_path = dir.join(MANIFEST_NAME);
+    if !manifest_path.exists() {
+        // interrupted before its first manifest write
+        fs::remove_dir_all(dir)?;
+        return Ok(())
+    }
+
+    let mut manifest: Manifest = serde_json::from_slice(&fs::read(manifest_path)?)?;
+    match manifest.state {
+        JournalState::Preparing => {
+            info!("Discarding unfinished {} from {}", manifest.operation, manifest.started_at);
+            fs::remove_dir_all(dir)?;
+        }
+        JournalState::Committing => {
+            let resumed = manifest.steps.iter().enumerate()
+                .try_for_each(|(index, step)| apply_step(dir, index, step));
+            match resumed {
+                Ok(()) => {
+                    info!("Resumed interrupted {} from {}", manifest.operation, manifest.started_at);
+                    fs::remove_dir_all(dir)?;
+                }
+                Err(e) => {
+                    warn!("Failed to resume {}, rolling back: {}", manifest.operation, e);
+                    rollback(dir, &mut manifest)?;
+                }
+            }
+        }
+        JournalState::RollingBack => {
+            info!("Finishing rollback of {} from {}", manifest.operation, manifest.started_at);
+            rollback(dir, &mut manifest)?;
+        }
+    }
+    Ok(())
+}
+
+// journals of this process may still be in use, those of earlier runs are the ones recover
+// couldn't finish
+pub fn is_leftover(dir: &Path) -> bool {
+    let id = dir.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    // "<date>-<time>-<pid>-<counter>", see Journal::begin
+    id.split('-').nth(2).and_then(|pid| pid.parse::<u32>().ok()) != Some(std::process::id())
+}
+
+// run once on startup, before anything else touches .weave
+pub fn recover(dirs: &WeaveDirs) -> Result<()> {
+    for entry in fs::read_dir(dirs.journal()?)? {
+        let dir = entry?.path();
+        if !dir.is_dir() {
+            continue
+        }
+        if let Err(e) = recover_journal(&dir) {
+            error!("Failed to recover journal {}: {}", dir.display(), e);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,575 @@
+// The manager is a library so the integration tests in tests/ can drive command logic against
+// a temporary WeaveDirs root and replay recordings, main.rs only calls run(). Modules the tests
+// use are public.
+
+pub mod error;
+mod faults;
+mod analytics;
+mod auto_attach;
+pub mod cleanup;
+mod console;
+mod debug;
+mod disk_space;
+mod download;
+mod features;
+mod game_profiles;
+mod github;
+mod gpu;
+mod instances;
+pub mod journal;
+pub mod loader;
+mod logging;
+mod metrics;
+mod mock;
+mod mod_updates;
+pub mod mods;
+mod notifications;
+mod overlay;
+pub mod paths;
+mod permissions;
+pub mod recording;
+mod search;
+mod settings;
+mod share;
+mod system_info;
+mod tasks;
+mod telemetry;
+mod wrappers;
+
+use std::collections::{HashMap, HashSet};
+use error::Result;
+use faults::Fault;
+
+use std::ffi::OsStr;
+use std::sync::{Mutex, Arc};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::env;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::fs::{File, read_dir, rename};
+use std::sync::atomic::{AtomicU32, Ordering};
+use serde::{Serialize, Deserialize};
+use serde_json;
+
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessRefreshKind, System, SystemExt};
+use tauri::{GlobalShortcutManager, Manager, State, SystemTrayEvent};
+use tauri::{SystemTray, SystemTrayMenu, CustomMenuItem, SystemTrayMenuItem};
+use tauri_plugin_autostart::MacosLauncher;
+use notifications::NotificationEvent;
+use recording::RecordedEvent;
+use zip::result::ZipError;
+use zip::ZipArchive;
+use chrono::prelude::Local;
+use tracing::{error, info};
+use data_encoding::HEXUPPER;
+use ring::digest::{Context, Digest, SHA256};
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ClientType {
+    Lunar,
+    Forge,
+    Labymod,
+    Vanilla,
+    Badlion,
+    Feather
+}
+#[derive(Serialize)]
+struct MinecraftProcess {
+    id: String,
+    pid: u32,
+    start_time: u64,
+    info: MinecraftInfo,
+    weave_attached: bool,
+    game_dir: String,
+    nickname: Option<String>
+}
+#[derive(Serialize, Deserialize)]
+struct MinecraftInfo {
+    client: ClientType,
+    version: String,
+    cmd: Vec<String>,
+    cwd: String
+}
+#[derive(Serialize, Deserialize)]
+struct ModProfile {
+    name: String, // names must be unique
+    mods: Vec<ModProfileEntry>
+}
+#[derive(Serialize, Deserialize)]
+struct ModProfileEntry {
+    config: Option<ModConfig>,
+    file_name: String // path is scoped in ~/.weave/mods
+}
+#[derive(Serialize, Deserialize)]
+struct ModConfig {
+    #[serde(default)]
+    id: Option<String>,
+    name: String,
+    version: String,
+    description: String,
+    authors: Vec<String>
+}
+impl Default for ModConfig {
+    fn default() -> Self {
+        ModConfig {
+            id: None,
+            name: "undefined".to_string(),
+            version: "undefined".to_string(),
+            description: "undefined".to_string(),
+            authors: Vec::new()
+        }
+    }
+}
+#[derive(Serialize, Deserialize)]
+struct LaunchProfile {
+    name: String,
+    mc_info: MinecraftInfo,
+    mod_profile: Option<ModProfile>
+}
+
+#[derive(Clone, Serialize)]
+struct WeaveProcess {
+    log_file: PathBuf,
+    client: ClientType,
+    pid: u32,
+    output: Vec<String>
+}
+
+fn sha256_digest<R: Read>(mut reader: R) -> Result<Digest> {
+    let mut context = Context::new(&SHA256);
+    let mut buffer = [0; 1024];
+
+    loop {
+        let count = reader.read(&mut buffer)?;
+        if count == 0 {
+            break;
+        }
+        context.update(&buffer[..count]);
+    }
+
+    Ok(context.finish())
+}
+
+fn sha256_hex<R: Read>(reader: R) -> Result<String> {
+    Ok(HEXUPPER.encode(sha256_digest(reader)?.as_ref()))
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+fn check_loader_integrity(sum_to_check: String, app_state: State<AppState>) -> Result<bool> {
+    let file = File::open(app_state.dirs.loader()?)?;
+    let digest = sha256_digest(file)?;
+    Ok(sum_to_check == HEXUPPER.encode(digest.as_ref()))
+}
+
+// jars without a readable weave.mod.json get the default config
+fn mod_config(dirs: &paths::WeaveDirs, path: &Path) -> Result<ModConfig> {
+    let file = File::open(path)?;
+    faults::inject_from(dirs, Fault::ZipRead)?;
+    let mut archive = ZipArchive::new(file)?;
+    let conf = match archive.by_name("weave.mod.json") {
+        Ok(conf) => conf,
+        Err(ZipError::FileNotFound) => return Ok(ModConfig::default()),
+        Err(e) => Err(e)?
+    };
+    Ok(serde_json::from_reader(conf).unwrap_or_default())
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+fn read_mod_config(path: String, app_state: State<AppState>) -> Result<Option<ModConfig>> {
+    Ok(Some(mod_config(&app_state.dirs, Path::new(&path))?))
+}
+
+fn minecraft_processes(app_state: &AppState) -> Vec<MinecraftProcess> {
+    let mut system = app_state.system.lock().unwrap();
+    system.refresh_processes_specifics(ProcessRefreshKind::new()); // refresh processes
+
+    if let Some(mock) = &app_state.mock {
+        return mock.fetch_processes(&system)
+    }
+
+    let nicknames = instances::read_nicknames().unwrap_or_default();
+
+    system.processes().values()
+        .filter_map(|proc| {
+            // If there are no java processes, return None
+            if !matches!(proc.exe().file_name().and_then(OsStr::to_str), Some("javaw.exe" | "java")) {
+                return None
+            }
+
+            // Rudimentary check for if the process is Minecraft
+            if !proc.cmd().iter().any(|arg| arg.contains("minecraft")) {
+                return None
+            }
+
+            // Determine the client type via command line arguments
+            let mut client_type = ClientType::Vanilla;
+            for arg in proc.cmd().iter() {
+                if arg.contains("lunar") {
+                    client_type = ClientType::Lunar;
+                    break;
+                } else if arg.contains("forge") {
+                    client_type = ClientType::Forge;
+                    break;
+                } else if arg.contains("labymod") {
+                    client_type = ClientType::Labymod;
+                    break;
+                }
+            }
+
+            let weave_attached = proc.cmd().iter().any(|arg| arg.contains("loader.jar") && arg.contains("-javaagent"));
+            let cwd = proc.cwd().to_string_lossy().to_string();
+            let game_dir = instances::game_dir(proc.cmd(), &cwd);
+
+            Some(MinecraftProcess {
+                id: instances::instance_id(&game_dir, proc.start_time()),
+                pid: proc.pid().as_u32(),
+                start_time: proc.start_time(),
+                info: MinecraftInfo {
+                    client: client_type,
+                    version: proc.cmd().iter().skip_while(|&arg| arg != "--version").nth(1)?.clone(),
+                    cmd: proc.cmd().to_owned(),
+                    cwd
+                },
+                weave_attached,
+                nickname: instances::nickname_for(&nicknames, &game_dir),
+                game_dir
+            })
+        }).collect()
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+fn fetch_minecraft_processes(app_state: State<AppState>) -> Vec<MinecraftProcess> {
+    minecraft_processes(&app_state)
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+fn fetch_instance_groups(app_state: State<AppState>) -> Vec<instances::InstanceGroup> {
+    instances::group_by_game_dir(minecraft_processes(&app_state))
+}
+
+// starts the game described by `mc` with the Weave agent, returns the new pid
+fn launch_with_weave(mc: MinecraftInfo, app: tauri::AppHandle) -> Result<u32> {
+    let app_state = app.state::<AppState>();
+    let game_dir = instances::game_dir(&mc.cmd, &mc.cwd);
+
+    // staged mod updates are swapped in while no game has the old jars loaded
+    if let Err(e) = mod_updates::apply_staged(&app_state) {
+        error!("Failed to apply staged mod updates: {}", e);
+    }
+
+    // the console is captured to a log file, checked before the game is started
+    disk_space::ensure_free_space(&app_state.dirs, &app_state.dirs.client_logs()?, 0)?;
+
+    // piped outputs
+    let (reader, writer) = os_pipe::pipe()?;
+
+    // mock instances are attached by spawning a dummy that produces console output instead
+    let mut command = match &app_state.mock {
+        Some(_) => mock::console_command(),
+        None => {
+            let weave_loader_path = app_state.dirs.loader()?;
+
+            // Insert the weave agent to the command line
+            let mut cmd = mc.cmd;
+            cmd.insert(1, format!("-javaagent:{}", weave_loader_path.to_str().unwrap()));
+
+            let mut command = Command::new(&cmd[0]);
+            command.args(&cmd[1..]);
+            gpu::apply_launch_fix(&mut command, &cmd[0]);
+            command
+        }
+    };
+
+    // spawn the process
+    let mut child = command
+        .current_dir(Path::new(&mc.cwd))
+        .stderr(writer.try_clone()?)
+        .stdout(writer)
+        .spawn()?;
+
+    if let Some(mock) = &app_state.mock {
+        mock.register_attached(child.id(), &mc.client, &mc.version, &mc.cwd);
+    }
+
+    // capture these values before moving into the closure
+    let instance_name = instances::read_nicknames().ok()
+        .and_then(|nicknames| instances::nickname_for(&nicknames, &game_dir))
+        .unwrap_or_else(|| format!("Minecraft {}", mc.version));
+    let log_dir = app_state.dirs.client_logs()?;
+    let log_name = Local::now().format("%Y-%m-%d-%H%M%S.log").to_string();
+    let log_path = log_dir.join(log_name);
+
+    info!("Launched Minecraft {} with Weave (PID {})", mc.version, child.id());
+
+    // select the most recent process spawned as the console output
+    let pid = child.id();
+    app_state.selected_process.store(pid, Ordering::Relaxed);
+
+    let mut session = analytics::Session::start(mc.client.clone());
+
+    // pipe the output to a file and emit an event containing the line
+    std::thread::spawn(move || {
+        let mut log_file = File::create(&log_path).expect("Failed to create log file");
+        let app_state = app.state::<AppState>();
+
+        let weave_process = WeaveProcess {
+            log_file: log_path,
+            client: mc.client.clone(),
+            pid: child.id(),
+            output: Vec::new()
+        };
+        app_state.weave_processes.lock().unwrap().insert(child.id(), weave_process.clone());
+        app_state.console.track_instance(child.id(), instance_name);
+        app.emit_all("spawned_weave", weave_process).expect("Failed to emit spawned_weave event to renderer");
+
+        recording::record(&app_state.recording, RecordedEvent::ProcessSpawned {
+            pid: child.id(),
+            client: mc.client.clone(),
+            version: mc.version.clone()
+        });
+        notifications::notify(&app, NotificationEvent::WeaveSpawned, Some(&game_dir),
+            "Weave launched", &format!("Started Minecraft {} with Weave (PID {})", mc.version, child.id()));
+
+        let buf_reader = BufReader::new(reader);
+
+        for line in buf_reader.lines().filter_map(|l| l.ok()) {
+            write!(log_file, "{}\n", line).expect("Failed to write output to log file");
+            recording::record(&app_state.recording, RecordedEvent::ConsoleLine {
+                pid: child.id(),
+                line: line.clone()
+            });
+            session.inspect_line(&line);
+            console::emit_line(&app, child.id(), line);
+        }
+
+        overlay::forget(&app_state.overlay, child.id());
+
+        let status = child.wait();
+        app_state.weave_processes.lock().unwrap().remove(&child.id());
+        let killed = app_state.killed_processes.lock().unwrap().remove(&child.id());
+        let crashed = session.crashed(status.as_ref().ok().and_then(|status| status.code()), killed);
+        if let Err(e) = analytics::record(session, crashed) {
+            error!("Failed to record analytics: {}", e);
+        }
+        if let Ok(status) = &status {
+            if crashed {
+                recording::record(&app_state.recording, RecordedEvent::ProcessCrashed { pid: child.id() });
+            }
+            recording::record(&app_state.recording, RecordedEvent::ProcessExited {
+                pid: child.id(),
+                code: status.code()
+            });
+            console::emit_exit(&app, child.id(), status.code());
+        }
+
+        match status {
+            Ok(_) if !crashed => notifications::notify(&app, NotificationEvent::WeaveExited, Some(&game_dir),
+                "Minecraft closed", &format!("Minecraft {} (PID {}) exited", mc.version, child.id())),
+            Ok(status) => notifications::notify(&app, NotificationEvent::WeaveCrashed, Some(&game_dir),
+                "Minecraft crashed", &format!("Minecraft {} (PID {}) exited with {}", mc.version, child.id(), status)),
+            Err(e) => error!("Failed to wait for Minecraft process: {}", e)
+        }
+    });
+
+    Ok(pid)
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+fn launch(profile: LaunchProfile, app: tauri::AppHandle) -> Result<()> {
+    launch_with_weave(profile.mc_info, app)?;
+    Ok(())
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+fn switch_console_output(pid: u32, app_state: State<AppState>) {
+    app_state.selected_process.store(pid, Ordering::Relaxed);
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+fn kill_pid(pid: u32, app_state: State<AppState>) -> bool {
+    mark_killed(&app_state, pid);
+    let killed = app_state.system.lock().unwrap().process(Pid::from_u32(pid)).is_some_and(|p| p.kill());
+    if !killed {
+        app_state.killed_processes.lock().unwrap().remove(&pid);
+    }
+    killed
+}
+
+// marked before the kill so the exit, which may be handled right away, isn't taken for a crash.
+// Only games launched by the manager are tracked, their exit handler unmarks them again.
+fn mark_killed(app_state: &AppState, pid: u32) {
+    if app_state.weave_processes.lock().unwrap().contains_key(&pid) {
+        app_state.killed_processes.lock().unwrap().insert(pid);
+    }
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+fn get_memory_usage(app_state: State<AppState>) -> (u64, u64) {
+    let mut sys = app_state.system.lock().unwrap();
+    sys.refresh_processes_specifics(ProcessRefreshKind::new());
+
+    let total = sys.total_memory();
+    let process = sys.process(sysinfo::get_current_pid().unwrap()).unwrap();
+    let used = process.memory();
+
+    (used, total)
+}
+
+pub struct AppState {
+    dirs: paths::WeaveDirs,
+    system: Mutex<System>,
+    selected_process: Arc<AtomicU32>,
+    weave_processes: Mutex<HashMap<u32, WeaveProcess>>,
+    // manager launched games being stopped on purpose, see mark_killed
+    killed_processes: Mutex<HashSet<u32>>,
+    overlay: overlay::OverlayState,
+    notification_rules: Mutex<notifications::NotificationRules>,
+    mock: Option<mock::MockProvider>,
+    console: console::ConsoleState,
+    recording: recording::RecordingState,
+    metrics: metrics::MetricsState,
+    tasks: tasks::TaskState
+}
+
+pub fn run() {
+    let _log_guard = logging::init();
+
+    let dirs = paths::WeaveDirs::from_home().expect("Home directory not found");
+    if let Err(e) = journal::recover(&dirs) {
+        error!("Failed to recover interrupted operations: {}", e);
+    }
+
+    let app_state = AppState {
+        dirs,
+        system: Mutex::new(System::new_all()),
+        selected_process: Arc::new(0.into()),
+        weave_processes: Mutex::new(HashMap::new()),
+        killed_processes: Mutex::new(HashSet::new()),
+        overlay: overlay::OverlayState::default(),
+        notification_rules: Mutex::new(notifications::read_rules().unwrap_or_default()),
+        mock: mock::MockProvider::from_env(),
+        console: console::ConsoleState::default(),
+        recording: recording::RecordingState::default(),
+        metrics: metrics::MetricsState::default(),
+        tasks: tasks::TaskState::default()
+    };
+
+    let tray_menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new("show", "Show"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new("quit", "Quit"));
+
+    let tray = SystemTray::new().with_menu(tray_menu);
+
+    tauri::Builder::default()
+        .plugin(tauri_plugin_fs_watch::init())
+        .plugin(tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, Some(vec!["--flag1", "--flag2"])))
+        .system_tray(tray)
+        .on_system_tray_event(|app, event| match event {
+            SystemTrayEvent::LeftClick { .. } => {
+                app.get_window("main").unwrap().show().unwrap()
+            }
+            SystemTrayEvent::MenuItemClick { id, .. } => {
+                match id.as_str() {
+                    "show" => app.get_window("main").unwrap().show().unwrap(),
+                    "quit" => std::process::exit(0),
+                    _ => {}
+                }
+            }
+            _ => {}
+        })
+        .setup(|app| {
+            features::refresh_remote_in_background();
+            mod_updates::start_background_checks(app.handle());
+            loader::start_background_checks(app.handle());
+            auto_attach::start_watcher(app.handle());
+            let handle = app.handle();
+            // another app may hold the shortcut already, the overlay stays reachable through toggle_overlay
+            let registered = app.global_shortcut_manager().register(overlay::OVERLAY_SHORTCUT, move || {
+                let handle = handle.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = overlay::toggle(&handle) {
+                        error!("Failed to toggle overlay: {}", e);
+                    }
+                });
+            });
+            if let Err(e) = registered {
+                error!("Failed to register overlay shortcut {}: {}", overlay::OVERLAY_SHORTCUT, e);
+            }
+            Ok(())
+        })
+        .manage(app_state)
+        .invoke_handler(tauri::generate_handler![
+            fetch_minecraft_processes,
+            fetch_instance_groups,
+            kill_pid,
+            get_memory_usage,
+            analytics::get_analytics,
+            analytics::reset_analytics,
+            analytics::export_analytics,
+            launch,
+            read_mod_config,
+            switch_console_output,
+            check_loader_integrity,
+            cleanup::cleanup,
+            search::search,
+            instances::get_instance_nicknames,
+            instances::set_instance_nickname,
+            overlay::toggle_overlay,
+            notifications::get_notification_rules,
+            notifications::set_notification_rules,
+            share::create_setup_link,
+            share::export_setup_file,
+            share::preview_setup_import,
+            share::import_setup,
+            permissions::repair_permissions,
+            console::replay_console_log,
+            console::stop_console_replay,
+            console::set_console_aggregate,
+            console::get_console_history,
+            recording::start_recording,
+            recording::stop_recording,
+            recording::replay_recording,
+            debug::dump_state,
+            logging::get_log_filter,
+            logging::set_log_filter,
+            telemetry::get_command_telemetry,
+            telemetry::reset_command_telemetry,
+            features::get_feature_flags,
+            metrics::get_instance_metrics,
+            gpu::get_gpu_info,
+            system_info::get_system_info,
+            wrappers::create_launcher_wrapper,
+            wrappers::get_launcher_wrappers,
+            wrappers::remove_launcher_wrapper,
+            mod_updates::get_mod_updates,
+            mod_updates::set_mod_update_source,
+            mod_updates::check_mod_updates,
+            mod_updates::rollback_mod_update,
+            loader::check_loader_health,
+            loader::repair_loader,
+            loader::check_loader_update,
+            loader::install_loader_update,
+            mods::get_mods,
+            mods::toggle_mod,
+            mods::install_mod_from_path,
+            mods::delete_mod,
+            auto_attach::get_auto_attach_clients,
+            auto_attach::set_auto_attach,
+            game_profiles::get_game_profiles,
+            game_profiles::create_game_profile,
+            game_profiles::update_game_profile,
+            game_profiles::delete_game_profile,
+            game_profiles::launch_profile
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
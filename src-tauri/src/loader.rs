@@ -0,0 +1,272 @@
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use serde::{Serialize, Deserialize};
+use tauri::{AppHandle, Manager, State};
+use tracing::{info, warn};
+
+use crate::download::{download, download_with_progress};
+use crate::error::Result;
+use crate::faults::{self, Fault};
+use crate::github::{self, Release};
+use crate::journal::{write_atomic, Journal};
+use crate::paths::WeaveDirs;
+use crate::settings::{get_setting_from, set_setting_from};
+use crate::tasks::TaskOutcome;
+use crate::{sha256_hex, AppState};
+
+const LOADER_REPO: &str = "Weave-MC/Weave-Loader";
+
+// antivirus software deleting or quarantining the jar is the usual culprit
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const TASK_NAME: &str = "loader_health";
+
+// the last status reported to the renderer, so a broken loader is only announced once
+static LAST_STATUS: Mutex<Option<LoaderStatus>> = Mutex::new(None);
+
+// version and hash of the loader.jar we last verified, kept next to it in loader.json
+#[derive(Serialize, Deserialize)]
+pub struct LoaderRecord {
+    version: String,
+    sha256: String
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum LoaderStatus {
+    Ok,
+    Missing,
+    Unreadable,
+    Corrupted,
+    // no version is recorded and it could not be looked up, e.g. while offline
+    Unverified
+}
+
+#[derive(Serialize)]
+pub struct LoaderUpdate {
+    installed_version: Option<String>,
+    latest_version: String,
+    // also set when the installed jar does not match its version, e.g. after a manual swap
+    update_available: bool
+}
+
+#[derive(Clone, Serialize)]
+struct DownloadProgress {
+    downloaded: u64,
+    total: Option<u64>
+}
+
+#[derive(Clone, Serialize)]
+pub struct LoaderHealth {
+    status: LoaderStatus,
+    version: Option<String>,
+    path: PathBuf
+}
+
+fn get_loader_path(dirs: &WeaveDirs) -> PathBuf {
+    dirs.root().join("loader.jar")
+}
+
+fn get_loader_record_path(dirs: &WeaveDirs) -> PathBuf {
+    dirs.root().join("loader.json")
+}
+
+fn read_record(dirs: &WeaveDirs) -> Option<LoaderRecord> {
+    let file = File::open(get_loader_record_path(dirs)).ok()?;
+    serde_json::from_reader(file).ok()
+}
+
+fn write_record(dirs: &WeaveDirs, record: &LoaderRecord) -> Result<()> {
+    write_atomic(&get_loader_record_path(dirs), &serde_json::to_vec_pretty(record)?)
+}
+
+// the jar asset of a loader release and its published hash, from the "<jar>.sha256" asset
+fn release_jar<'a>(dirs: &WeaveDirs, release: &'a Release) -> Result<(&'a github::ReleaseAsset, String)> {
+    let jar = release.assets.iter()
+        .find(|asset| asset.name.ends_with(".jar"))
+        .ok_or_else(|| format!("Weave-Loader {} has no jar to download", release.tag_name))?;
+
+    let sha256 = match release.assets.iter().find(|asset| asset.name == format!("{}.sha256", jar.name)) {
+        Some(asset) => {
+            let sha256 = String::from_utf8_lossy(&download(dirs, &asset.browser_download_url)?).to_string();
+            sha256.split_whitespace().next().unwrap_or_default().to_string()
+        }
+        None => jar.sha256().ok_or_else(|| format!("Weave-Loader {} has no published checksum", release.tag_name))?.to_string()
+    };
+    Ok((jar, sha256))
+}
+
+// The record is bootstrapped from GitHub for installs made before loader.json existed, and
+// refreshed when the frontend has installed another version since it was written.
+fn expected_record(dirs: &WeaveDirs) -> Option<LoaderRecord> {
+    let installed_version = get_setting_from::<String>(dirs, "loader_version");
+    if let Some(record) = read_record(dirs) {
+        if installed_version.is_none() || installed_version.as_ref() == Some(&record.version) {
+            return Some(record)
+        }
+    }
+
+    let version = installed_version?;
+    let release = github::release_by_tag(dirs, LOADER_REPO, &version).ok()?;
+    let (_, sha256) = release_jar(dirs, &release).ok()?;
+    Some(LoaderRecord { version, sha256 })
+}
+
+pub fn check_health(dirs: &WeaveDirs) -> Result<LoaderHealth> {
+    let path = get_loader_path(dirs);
+    let expected = expected_record(dirs);
+    let version = expected.as_ref().map(|record| record.version.clone());
+
+    let status = if !path.exists() {
+        LoaderStatus::Missing
+    } else {
+        match File::open(&path).map_err(Into::into).and_then(sha256_hex) {
+            Err(_) => LoaderStatus::Unreadable,
+            Ok(sha256) => match &expected {
+                Some(record) if record.sha256.eq_ignore_ascii_case(&sha256) => {
+                    if read_record(dirs).map(|recorded| recorded.version).as_ref() != Some(&record.version) {
+                        write_record(dirs, record)?;
+                    }
+                    LoaderStatus::Ok
+                }
+                Some(_) => LoaderStatus::Corrupted,
+                None => LoaderStatus::Unverified
+            }
+        }
+    };
+
+    Ok(LoaderHealth { status, version, path })
+}
+
+pub fn start_background_checks(app: AppHandle) {
+    let dirs = app.state::<AppState>().dirs.clone();
+    app.state::<AppState>().tasks.register(TASK_NAME);
+    thread::spawn(move || loop {
+        let outcome = match check_health(&dirs) {
+            Ok(health) => {
+                let mut last_status = LAST_STATUS.lock().unwrap();
+                if *last_status != Some(health.status) {
+                    if !matches!(health.status, LoaderStatus::Ok | LoaderStatus::Unverified) {
+                        warn!("Weave-Loader at {} is {:?}", health.path.display(), health.status);
+                        let _ = app.emit_all("loader_broken", health.clone());
+                    }
+                    *last_status = Some(health.status);
+                }
+                TaskOutcome::Ok
+            }
+            Err(e) => {
+                warn!("Failed to check the Weave-Loader: {}", e);
+                TaskOutcome::Failed(e.to_string())
+            }
+        };
+        app.state::<AppState>().tasks.record(TASK_NAME, outcome);
+        thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+// the frontend's setting wins, it is updated whenever the frontend installs a loader itself
+fn installed_version(dirs: &WeaveDirs) -> Option<String> {
+    get_setting_from(dirs, "loader_version").or_else(|| read_record(dirs).map(|record| record.version))
+}
+
+// the jar and its record are replaced together, so the health check never sees a new jar with
+// the old hash
+fn replace_loader(dirs: &WeaveDirs, operation: &str, jar: &[u8], record: &LoaderRecord) -> Result<()> {
+    faults::inject_from(dirs, Fault::DiskWrite)?;
+    let mut journal = Journal::begin(dirs, operation)?;
+    journal.stage_write(get_loader_path(dirs), jar)?;
+    journal.stage_write(get_loader_record_path(dirs), &serde_json::to_vec_pretty(record)?)?;
+    journal.commit()
+}
+
+fn repair(dirs: &WeaveDirs) -> Result<LoaderHealth> {
+    let version = installed_version(dirs);
+    let release = match &version {
+        Some(version) => github::release_by_tag(dirs, LOADER_REPO, version)?,
+        None => github::latest_release(dirs, LOADER_REPO)?
+    };
+
+    let (jar, sha256) = release_jar(dirs, &release)?;
+    let bytes = download(dirs, &jar.browser_download_url)?;
+    if !sha256_hex(bytes.as_slice())?.eq_ignore_ascii_case(&sha256) {
+        Err(format!("Checksum mismatch for Weave-Loader {} downloaded from {}", release.tag_name, jar.browser_download_url))?;
+    }
+
+    replace_loader(dirs, "repair_loader", &bytes, &LoaderRecord { version: release.tag_name.clone(), sha256 })?;
+    info!("Repaired Weave-Loader {}", release.tag_name);
+
+    // verify from disk, an antivirus may take the fresh copy right away too
+    let health = check_health(dirs)?;
+    *LAST_STATUS.lock().unwrap() = Some(health.status);
+    Ok(health)
+}
+
+fn check_update(dirs: &WeaveDirs) -> Result<LoaderUpdate> {
+    let release = github::latest_release(dirs, LOADER_REPO)?;
+    let (_, sha256) = release_jar(dirs, &release)?;
+
+    let installed_version = installed_version(dirs);
+    let installed_sha256 = File::open(get_loader_path(dirs)).map_err(Into::into).and_then(sha256_hex).ok();
+    let update_available = installed_version.as_ref() != Some(&release.tag_name)
+        || !installed_sha256.is_some_and(|installed| installed.eq_ignore_ascii_case(&sha256));
+
+    Ok(LoaderUpdate {
+        installed_version,
+        latest_version: release.tag_name,
+        update_available
+    })
+}
+
+fn install_update(app: &AppHandle, dirs: &WeaveDirs) -> Result<LoaderHealth> {
+    let release = github::latest_release(dirs, LOADER_REPO)?;
+    let (jar, sha256) = release_jar(dirs, &release)?;
+
+    let bytes = download_with_progress(dirs, &jar.browser_download_url, |downloaded, total| {
+        let _ = app.emit_all("loader_update_progress", DownloadProgress { downloaded, total });
+    })?;
+    if !sha256_hex(bytes.as_slice())?.eq_ignore_ascii_case(&sha256) {
+        Err(format!("Checksum mismatch for Weave-Loader {} downloaded from {}", release.tag_name, jar.browser_download_url))?;
+    }
+
+    replace_loader(dirs, "install_loader_update", &bytes, &LoaderRecord { version: release.tag_name.clone(), sha256 })?;
+
+    set_setting_from(dirs, "loader_version", &release.tag_name)?;
+    let _ = app.emit_all("loader_updated", &release.tag_name);
+    info!("Updated Weave-Loader to {}", release.tag_name);
+
+    let health = check_health(dirs)?;
+    *LAST_STATUS.lock().unwrap() = Some(health.status);
+    Ok(health)
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub async fn check_loader_update(app_state: State<'_, AppState>) -> Result<LoaderUpdate> {
+    let dirs = app_state.dirs.clone();
+    tauri::async_runtime::spawn_blocking(move || check_update(&dirs)).await?
+}
+
+// emits loader_update_progress while downloading and loader_updated with the new version,
+// which has to be mirrored into the frontend's copy of the settings
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub async fn install_loader_update(app: AppHandle) -> Result<LoaderHealth> {
+    let dirs = app.state::<AppState>().dirs.clone();
+    tauri::async_runtime::spawn_blocking(move || install_update(&app, &dirs)).await?
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub async fn check_loader_health(app_state: State<'_, AppState>) -> Result<LoaderHealth> {
+    let dirs = app_state.dirs.clone();
+    tauri::async_runtime::spawn_blocking(move || check_health(&dirs)).await?
+}
+
+// re-downloads the recorded version, or the latest one if nothing is known about the install
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub async fn repair_loader(app_state: State<'_, AppState>) -> Result<LoaderHealth> {
+    let dirs = app_state.dirs.clone();
+    tauri::async_runtime::spawn_blocking(move || repair(&dirs)).await?
+}
@@ -0,0 +1,70 @@
+use std::io;
+use std::sync::OnceLock;
+use chrono::Utc;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::error::Result;
+use crate::paths::get_weave_manager_logs_path;
+use crate::telemetry::{self, TelemetryLayer};
+
+const DEFAULT_FILTER: &str = "info";
+const LOG_FILE_NAME: &str = "manager.log";
+
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+// Logs go to stderr and a daily rolling file in ~/.weave/logs/manager. The returned guard
+// flushes the file writer and has to be kept alive until the app exits.
+pub fn init() -> Option<WorkerGuard> {
+    let filter = EnvFilter::try_from_env("WEAVE_LOG").unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let (filter, handle) = reload::Layer::new(filter);
+    let _ = FILTER_HANDLE.set(handle);
+
+    let (file_writer, guard) = match get_weave_manager_logs_path() {
+        Ok(log_dir) => {
+            let (writer, guard) = tracing_appender::non_blocking(tracing_appender::rolling::daily(log_dir, LOG_FILE_NAME));
+            (Some(writer), Some(guard))
+        }
+        Err(_) => (None, None)
+    };
+
+    let log_layers = fmt::layer().with_writer(io::stderr)
+        .and_then(file_writer.map(|writer| fmt::layer().with_writer(writer).with_ansi(false)))
+        .with_filter(filter);
+
+    // command telemetry is collected regardless of the log filter
+    tracing_subscriber::registry()
+        .with(log_layers)
+        .with(TelemetryLayer.with_filter(filter_fn(telemetry::is_command)))
+        .init();
+
+    guard
+}
+
+// the appender starts a new "manager.log.YYYY-MM-DD" file every day (UTC) and never removes old
+// ones, cleanup does. Today's file is still being written to.
+pub fn is_old_log_file(file_name: &str) -> bool {
+    let today = format!("{}.{}", LOG_FILE_NAME, Utc::now().format("%Y-%m-%d"));
+    file_name.starts_with(&format!("{}.", LOG_FILE_NAME)) && file_name != today
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn get_log_filter() -> Result<String> {
+    let handle = FILTER_HANDLE.get().ok_or("Logging is not initialized")?;
+    handle.with_current(|filter| filter.to_string()).map_err(|e| e.to_string().into())
+}
+
+// accepts any EnvFilter directive, e.g. "debug" or "info,weave_manager::console=trace"
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn set_log_filter(filter: String) -> Result<()> {
+    let handle = FILTER_HANDLE.get().ok_or("Logging is not initialized")?;
+    let filter = EnvFilter::try_new(&filter).map_err(|e| format!("Invalid log filter: {}", e))?;
+    handle.reload(filter).map_err(|e| e.to_string())?;
+    tracing::info!("Log filter changed to {}", handle.with_current(|filter| filter.to_string()).unwrap_or_default());
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+use serde::Serialize;
+use sysinfo::{Pid, PidExt, ProcessExt, SystemExt};
+use tauri::State;
+
+use crate::error::Result;
+use crate::AppState;
+
+#[derive(Serialize)]
+pub struct SystemNetworkUsage {
+    rx_bytes: u64,
+    tx_bytes: u64,
+    // averaged since the previous call for the same pid, 0 on the first one
+    rx_bytes_per_sec: f64,
+    tx_bytes_per_sec: f64
+}
+
+#[derive(Serialize)]
+pub struct DiskIo {
+    read_bytes: u64,
+    written_bytes: u64,
+    // since the previous process refresh, which every call to get_instance_metrics does
+    recent_read_bytes: u64,
+    recent_written_bytes: u64
+}
+
+#[derive(Serialize)]
+pub struct InstanceMetrics {
+    pid: u32,
+    cpu_usage: f32,
+    memory: u64,
+    disk_io: DiskIo,
+    // No platform exposes per-process traffic without elevated privileges, so this is the
+    // traffic of the whole network namespace the instance runs in, i.e. of the machine outside
+    // of containers. Only set on Linux.
+    system_network: Option<SystemNetworkUsage>
+}
+
+struct NetworkSample {
+    rx_bytes: u64,
+    tx_bytes: u64,
+    at: Instant
+}
+
+#[derive(Default)]
+pub struct MetricsState {
+    network_samples: Mutex<HashMap<u32, NetworkSample>>
+}
+
+// /proc/<pid>/net/dev reports the counters of the network namespace the process lives in,
+// which outside of containers is shared by every process on the machine
+#[cfg(target_os = "linux")]
+fn read_network_counters(pid: u32) -> Option<(u64, u64)> {
+    let dev = std::fs::read_to_string(format!("/proc/{}/net/dev", pid)).ok()?;
+
+    // two header lines, then "iface: rx_bytes rx_packets ... tx_bytes ..." per interface
+    let counters = dev.lines().skip(2)
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(iface, _)| iface.trim() != "lo")
+        .filter_map(|(_, stats)| {
+            let stats: Vec<u64> = stats.split_whitespace().filter_map(|stat| stat.parse().ok()).collect();
+            Some((*stats.first()?, *stats.get(8)?))
+        })
+        .fold((0, 0), |(rx, tx), (iface_rx, iface_tx)| (rx + iface_rx, tx + iface_tx));
+    Some(counters)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_network_counters(_pid: u32) -> Option<(u64, u64)> {
+    None
+}
+
+impl MetricsState {
+    fn network_usage(&self, pid: u32) -> Option<SystemNetworkUsage> {
+        let (rx_bytes, tx_bytes) = read_network_counters(pid)?;
+        let now = Instant::now();
+
+        let mut samples = self.network_samples.lock().unwrap();
+        let (rx_bytes_per_sec, tx_bytes_per_sec) = match samples.get(&pid) {
+            Some(previous) => {
+                let elapsed = now.duration_since(previous.at).as_secs_f64().max(f64::EPSILON);
+                (
+                    rx_bytes.saturating_sub(previous.rx_bytes) as f64 / elapsed,
+                    tx_bytes.saturating_sub(previous.tx_bytes) as f64 / elapsed
+                )
+            }
+            None => (0.0, 0.0)
+        };
+        samples.insert(pid, NetworkSample { rx_bytes, tx_bytes, at: now });
+
+        Some(SystemNetworkUsage {
+            rx_bytes,
+            tx_bytes,
+            rx_bytes_per_sec,
+            tx_bytes_per_sec
+        })
+    }
+
+    fn forget(&self, pid: u32) {
+        self.network_samples.lock().unwrap().remove(&pid);
+    }
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn get_instance_metrics(pid: u32, app_state: State<AppState>) -> Result<InstanceMetrics> {
+    let mut system = app_state.system.lock().unwrap();
+    if !system.refresh_process(Pid::from_u32(pid)) {
+        app_state.metrics.forget(pid);
+        Err(format!("Process {} is not running", pid))?;
+    }
+    let process = system.process(Pid::from_u32(pid)).ok_or("Process not found")?;
+    let disk_usage = process.disk_usage();
+
+    Ok(InstanceMetrics {
+        pid,
+        cpu_usage: process.cpu_usage(),
+        memory: process.memory(),
+        disk_io: DiskIo {
+            read_bytes: disk_usage.total_read_bytes,
+            written_bytes: disk_usage.total_written_bytes,
+            recent_read_bytes: disk_usage.read_bytes,
+            recent_written_bytes: disk_usage.written_bytes
+        },
+        system_network: app_state.metrics.network_usage(pid)
+    })
+}
@@ -0,0 +1,154 @@
+use std::env;
+use std::fs::File;
+use std::process::{Child, Command};
+use std::sync::Mutex;
+use serde::Deserialize;
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+use tracing::warn;
+
+use crate::error::Result;
+use crate::{instances, ClientType, MinecraftInfo, MinecraftProcess};
+
+// Points at a JSON fixture of fake instances. Only honored in debug builds so contributors and
+// CI can exercise attach, console and kill flows without Minecraft installed.
+const MOCK_INSTANCES_ENV: &str = "WEAVE_MOCK_INSTANCES";
+
+#[derive(Clone, Deserialize)]
+struct MockInstance {
+    client: ClientType,
+    version: String,
+    #[serde(default)]
+    cwd: Option<String>
+}
+
+struct MockProcess {
+    pid: u32,
+    instance: MockInstance,
+    weave_attached: bool
+}
+
+pub struct MockProvider {
+    processes: Mutex<Vec<MockProcess>>,
+    // keeps the idle dummies alive for as long as the manager runs
+    children: Mutex<Vec<Child>>
+}
+
+// Dummies poll their parent and exit on their own once the manager is gone, so nothing
+// is left behind when the app quits without cleaning up.
+#[cfg(unix)]
+fn dummy_command(script: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(format!("while kill -0 $PPID 2>/dev/null; do {} sleep 1; done", script));
+    command
+}
+
+#[cfg(windows)]
+fn dummy_command(script: &str) -> Command {
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-Command", &format!(
+        "while (Get-Process -Id {} -ErrorAction SilentlyContinue) {{ {} Start-Sleep 1 }}",
+        std::process::id(), script
+    )]);
+    command
+}
+
+#[cfg(unix)]
+pub fn console_command() -> Command {
+    dummy_command(r#"echo "[$(date +%H:%M:%S)] [Client thread/INFO]: Mock instance tick";"#)
+}
+
+#[cfg(windows)]
+pub fn console_command() -> Command {
+    dummy_command("Write-Output ('[' + (Get-Date -Format HH:mm:ss) + '] [Client thread/INFO]: Mock instance tick');")
+}
+
+fn mock_cwd(instance: &MockInstance) -> String {
+    instance.cwd.clone().unwrap_or_else(|| env::temp_dir().to_string_lossy().to_string())
+}
+
+impl MockProvider {
+    pub fn from_env() -> Option<MockProvider> {
+        if !cfg!(debug_assertions) {
+            return None
+        }
+
+        let fixture = env::var(MOCK_INSTANCES_ENV).ok()?;
+        match MockProvider::load(&fixture) {
+            Ok(provider) => Some(provider),
+            Err(e) => {
+                warn!("Failed to load mock instances from {}: {}", fixture, e);
+                None
+            }
+        }
+    }
+
+    fn load(fixture: &str) -> Result<MockProvider> {
+        let instances: Vec<MockInstance> = serde_json::from_reader(File::open(fixture)?)?;
+
+        let mut processes = Vec::new();
+        let mut children = Vec::new();
+        for instance in instances {
+            let child = dummy_command("").spawn()?;
+            processes.push(MockProcess {
+                pid: child.id(),
+                instance,
+                weave_attached: false
+            });
+            children.push(child);
+        }
+
+        Ok(MockProvider {
+            processes: Mutex::new(processes),
+            children: Mutex::new(children)
+        })
+    }
+
+    // attaching a mock instance spawns a console dummy, record it so it shows up as a weave process
+    pub fn register_attached(&self, pid: u32, client: &ClientType, version: &str, cwd: &str) {
+        self.processes.lock().unwrap().push(MockProcess {
+            pid,
+            instance: MockInstance {
+                client: client.clone(),
+                version: version.to_string(),
+                cwd: Some(cwd.to_string())
+            },
+            weave_attached: true
+        });
+    }
+
+    pub fn fetch_processes(&self, system: &System) -> Vec<MinecraftProcess> {
+        // reap killed dummies first, zombies would otherwise still show up as running
+        self.children.lock().unwrap().retain_mut(|child| matches!(child.try_wait(), Ok(None)));
+        let mut processes = self.processes.lock().unwrap();
+        processes.retain(|mock| system.process(Pid::from_u32(mock.pid)).is_some());
+
+        processes.iter()
+            .filter_map(|mock| {
+                let process = system.process(Pid::from_u32(mock.pid))?;
+                let cwd = mock_cwd(&mock.instance);
+                let cmd = vec![
+                    "java".to_string(),
+                    "net.minecraft.client.main.Main".to_string(),
+                    "--version".to_string(),
+                    mock.instance.version.clone()
+                ];
+
+                let game_dir = instances::game_dir(&cmd, &cwd);
+                Some(MinecraftProcess {
+                    id: instances::instance_id(&game_dir, process.start_time()),
+                    pid: mock.pid,
+                    start_time: process.start_time(),
+                    weave_attached: mock.weave_attached,
+                    game_dir,
+                    nickname: None,
+                    info: MinecraftInfo {
+                        client: mock.instance.client.clone(),
+                        version: mock.instance.version.clone(),
+                        cmd,
+                        cwd
+                    }
+                })
+            })
+            .collect()
+    }
+}
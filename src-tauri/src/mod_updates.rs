@@ -0,0 +1,354 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use serde::{Serialize, Deserialize};
+use sysinfo::{ProcessExt, ProcessRefreshKind, SystemExt};
+use tauri::{AppHandle, Manager, State};
+use tracing::{error, info, warn};
+use zip::ZipArchive;
+
+use crate::download::download;
+use crate::error::Result;
+use crate::github;
+use crate::journal::{write_atomic, Journal};
+use crate::mods::{check_file_name, DISABLED_SUFFIX};
+use crate::paths::WeaveDirs;
+use crate::settings::get_setting;
+use crate::tasks::TaskOutcome;
+use crate::{sha256_hex, AppState};
+
+// Mods with a GitHub releases source are checked in the background while "auto_update_mods" is
+// enabled. New versions are downloaded and verified into ~/.weave/updates/staged and only
+// swapped into the mods folder right before the next launch, when no game has them loaded.
+// The replaced jar is kept in ~/.weave/updates/previous for a rollback.
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const TASK_NAME: &str = "mod_updates";
+
+// serializes read-modify-write cycles of updates.json between the watcher and commands
+pub(crate) static UPDATES_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ModUpdateSource {
+    // GitHub "owner/name"
+    repo: String,
+    // picks the release asset whose name contains this, otherwise the first jar
+    #[serde(default)]
+    asset: Option<String>,
+    // release tag of the jar currently in the mods folder, None until the first update
+    #[serde(default)]
+    installed_version: Option<String>,
+    // release tag that was rolled back, not staged again until a newer release replaces it
+    #[serde(default)]
+    skipped_version: Option<String>
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ModVersion {
+    version: Option<String>,
+    sha256: String
+}
+
+// keyed by mod file name
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModUpdates {
+    sources: BTreeMap<String, ModUpdateSource>,
+    staged: BTreeMap<String, ModVersion>,
+    previous: BTreeMap<String, ModVersion>
+}
+
+impl ModUpdates {
+    pub(crate) fn is_staged(&self, file_name: &str) -> bool {
+        self.staged.contains_key(file_name)
+    }
+
+    pub(crate) fn has_previous(&self, file_name: &str) -> bool {
+        self.previous.contains_key(file_name)
+    }
+}
+
+fn get_updates_path(dirs: &WeaveDirs) -> Result<PathBuf> {
+    Ok(dirs.updates()?.join("updates.json"))
+}
+
+pub(crate) fn read_updates(dirs: &WeaveDirs) -> Result<ModUpdates> {
+    let path = get_updates_path(dirs)?;
+    if !path.exists() {
+        return Ok(ModUpdates::default())
+    }
+    Ok(serde_json::from_reader(File::open(path)?)?)
+}
+
+fn write_updates(dirs: &WeaveDirs, updates: &ModUpdates) -> Result<()> {
+    write_atomic(&get_updates_path(dirs)?, &serde_json::to_vec_pretty(updates)?)
+}
+
+fn select_asset<'a>(release: &'a github::Release, source: &ModUpdateSource) -> Option<&'a github::ReleaseAsset> {
+    release.assets.iter().find(|asset| match &source.asset {
+        Some(pattern) => asset.name.contains(pattern.as_str()),
+        None => asset.name.ends_with(".jar") && !asset.name.ends_with("-sources.jar")
+    })
+}
+
+// downloads and verifies the latest release of one mod, returns it if it is new
+fn download_update(dirs: &WeaveDirs, source: &ModUpdateSource, staged: Option<&ModVersion>) -> Result<Option<(ModVersion, Vec<u8>)>> {
+    let release = github::latest_release(dirs, &source.repo)?;
+    let version = Some(release.tag_name.clone());
+    if source.installed_version == version || source.skipped_version == version
+        || staged.is_some_and(|staged| staged.version == version) {
+        return Ok(None)
+    }
+
+    let asset = select_asset(&release, source)
+        .ok_or_else(|| format!("Release {} of {} has no matching jar", release.tag_name, source.repo))?;
+    let bytes = download(dirs, &asset.browser_download_url)?;
+
+    let sha256 = sha256_hex(bytes.as_slice())?;
+    if asset.sha256().is_some_and(|expected| !expected.eq_ignore_ascii_case(&sha256)) {
+        Err(format!("Checksum mismatch for {} downloaded from {}", asset.name, asset.browser_download_url))?;
+    }
+    // a truncated or html error page download would otherwise only fail once the game loads it
+    ZipArchive::new(Cursor::new(bytes.as_slice()))?;
+
+    Ok(Some((ModVersion { version, sha256 }, bytes)))
+}
+
+// Returns the names of mods with a newly staged update. The downloads run without the lock so
+// commands and launches aren't held up, the results are merged into a fresh read afterwards.
+fn check_for_updates(dirs: &WeaveDirs) -> Result<Vec<String>> {
+    let updates = {
+        let _lock = UPDATES_LOCK.lock().unwrap();
+        read_updates(dirs)?
+    };
+    let mods_dir = dirs.mods()?;
+
+    let mut downloaded = Vec::new();
+    for (file_name, source) in &updates.sources {
+        if check_file_name(file_name).is_err() || !mods_dir.join(file_name).exists() {
+            continue
+        }
+        match download_update(dirs, source, updates.staged.get(file_name)) {
+            Ok(Some((version, bytes))) => downloaded.push((file_name.clone(), source.repo.clone(), version, bytes)),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to check {} for updates: {}", file_name, e)
+        }
+    }
+
+    let _lock = UPDATES_LOCK.lock().unwrap();
+    let mut updates = read_updates(dirs)?;
+    let staged_dir = dirs.staged_updates()?;
+    let mut newly_staged = Vec::new();
+    for (file_name, repo, version, bytes) in downloaded {
+        // the source was removed or changed while downloading
+        if updates.sources.get(&file_name).map(|source| &source.repo) != Some(&repo) {
+            continue
+        }
+        write_atomic(&staged_dir.join(&file_name), &bytes)?;
+        info!("Staged {} {} for the next launch", file_name, version.version.as_deref().unwrap_or_default());
+        updates.staged.insert(file_name.clone(), version);
+        newly_staged.push(file_name);
+    }
+
+    write_updates(dirs, &updates)?;
+    Ok(newly_staged)
+}
+
+pub fn start_background_checks(app: AppHandle) {
+    let dirs = app.state::<AppState>().dirs.clone();
+    app.state::<AppState>().tasks.register(TASK_NAME);
+    thread::spawn(move || loop {
+        let outcome = if get_setting::<bool>("auto_update_mods").unwrap_or(false) {
+            match check_for_updates(&dirs) {
+                Ok(staged) => {
+                    if !staged.is_empty() {
+                        let _ = app.emit_all("mod_updates_staged", staged);
+                    }
+                    TaskOutcome::Ok
+                }
+                Err(e) => {
+                    error!("Failed to check for mod updates: {}", e);
+                    TaskOutcome::Failed(e.to_string())
+                }
+            }
+        } else {
+            TaskOutcome::Disabled
+        };
+        app.state::<AppState>().tasks.record(TASK_NAME, outcome);
+        thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+// a mod disabled since it was updated or staged is replaced under its disabled name, so it
+// stays disabled and cleanup doesn't take the disabled copy for an orphan
+fn installed_path(mods_dir: &Path, file_name: &str) -> PathBuf {
+    let enabled = mods_dir.join(file_name);
+    let disabled = mods_dir.join(format!("{}{}", file_name, DISABLED_SUFFIX));
+    if !enabled.exists() && disabled.exists() { disabled } else { enabled }
+}
+
+// swapping jars under a running game breaks it, or fails outright on Windows
+fn is_weave_running(app_state: &AppState) -> bool {
+    if !app_state.weave_processes.lock().unwrap().is_empty() {
+        return true
+    }
+
+    // games started through a launcher wrapper are not tracked by the manager
+    let mut system = app_state.system.lock().unwrap();
+    system.refresh_processes_specifics(ProcessRefreshKind::new());
+    let running = system.processes().values()
+        .any(|proc| proc.cmd().iter().any(|arg| arg.contains("loader.jar") && arg.contains("-javaagent")));
+    running
+}
+
+// Swaps every staged update into the mods folder in one journaled operation. Called right
+// before launching, a failure leaves the staged updates in place for the next attempt.
+pub fn apply_staged(app_state: &AppState) -> Result<()> {
+    let dirs = &app_state.dirs;
+    let _lock = UPDATES_LOCK.lock().unwrap();
+    let mut updates = read_updates(dirs)?;
+    if updates.staged.is_empty() || is_weave_running(app_state) {
+        return Ok(())
+    }
+
+    let mods_dir = dirs.mods()?;
+    let staged_dir = dirs.staged_updates()?;
+    let previous_dir = dirs.previous_mods()?;
+
+    let mut replaced = HashSet::new();
+    let mut dropped = Vec::new();
+    let mut invalid = Vec::new();
+    let mut journal = Journal::begin(dirs, "apply_mod_updates")?;
+    for (file_name, version) in &updates.staged {
+        // a hand-edited updates.json must not write outside the mods and updates folders
+        if let Err(e) = check_file_name(file_name) {
+            warn!("Dropped the staged update for {}: {}", file_name, e);
+            invalid.push(file_name.clone());
+            continue
+        }
+        // checked again, the staged jar sat on disk since it was downloaded. A broken one is
+        // thrown away instead of failing every launch from now on, the next check stages it again
+        let staged = fs::read(staged_dir.join(file_name)).ok()
+            .filter(|staged| sha256_hex(staged.as_slice()).is_ok_and(|sha256| sha256.eq_ignore_ascii_case(&version.sha256)));
+        let Some(staged) = staged else {
+            warn!("Dropped the staged update for {}, the jar is missing or corrupted", file_name);
+            dropped.push(file_name.clone());
+            continue
+        };
+
+        let installed = installed_path(&mods_dir, file_name);
+        if installed.exists() {
+            journal.stage_write(previous_dir.join(file_name), &fs::read(&installed)?)?;
+            replaced.insert(file_name.clone());
+        }
+        journal.stage_write(installed, &staged)?;
+    }
+    journal.commit()?;
+
+    for file_name in invalid {
+        updates.staged.remove(&file_name);
+    }
+    for file_name in dropped {
+        updates.staged.remove(&file_name);
+        let _ = fs::remove_file(staged_dir.join(&file_name));
+    }
+    for (file_name, version) in std::mem::take(&mut updates.staged) {
+        let _ = fs::remove_file(staged_dir.join(&file_name));
+        if replaced.contains(&file_name) {
+            let installed_version = updates.sources.get(&file_name).and_then(|source| source.installed_version.clone());
+            updates.previous.insert(file_name.clone(), ModVersion {
+                version: installed_version,
+                sha256: sha256_hex(File::open(previous_dir.join(&file_name))?)?
+            });
+        }
+        if let Some(source) = updates.sources.get_mut(&file_name) {
+            source.installed_version = version.version;
+        }
+        info!("Updated {}", file_name);
+    }
+    write_updates(dirs, &updates)
+}
+
+// the commands below wait for UPDATES_LOCK, so they stay off the main thread
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub async fn get_mod_updates(app_state: State<'_, AppState>) -> Result<ModUpdates> {
+    let dirs = app_state.dirs.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let _lock = UPDATES_LOCK.lock().unwrap();
+        read_updates(&dirs)
+    }).await?
+}
+
+fn set_update_source(dirs: &WeaveDirs, file_name: String, source: Option<ModUpdateSource>) -> Result<()> {
+    check_file_name(&file_name)?;
+    let _lock = UPDATES_LOCK.lock().unwrap();
+    let mut updates = read_updates(dirs)?;
+    match source {
+        Some(source) => {
+            updates.sources.insert(file_name, source);
+        }
+        None => {
+            updates.sources.remove(&file_name);
+            if updates.staged.remove(&file_name).is_some() {
+                let _ = fs::remove_file(dirs.staged_updates()?.join(&file_name));
+            }
+        }
+    }
+    write_updates(dirs, &updates)
+}
+
+// None stops updating the mod
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub async fn set_mod_update_source(file_name: String, source: Option<ModUpdateSource>, app_state: State<'_, AppState>) -> Result<()> {
+    let dirs = app_state.dirs.clone();
+    tauri::async_runtime::spawn_blocking(move || set_update_source(&dirs, file_name, source)).await?
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub async fn check_mod_updates(app_state: State<'_, AppState>) -> Result<Vec<String>> {
+    let dirs = app_state.dirs.clone();
+    tauri::async_runtime::spawn_blocking(move || check_for_updates(&dirs)).await?
+}
+
+// puts the jar replaced by the last update back and forgets that update
+fn rollback(file_name: String, app_state: &AppState) -> Result<()> {
+    let dirs = &app_state.dirs;
+    check_file_name(&file_name)?;
+    if is_weave_running(app_state) {
+        Err("Close Minecraft before rolling back a mod update")?;
+    }
+
+    let _lock = UPDATES_LOCK.lock().unwrap();
+    let mut updates = read_updates(dirs)?;
+    let previous = updates.previous.remove(&file_name).ok_or_else(|| format!("No previous version of {} to roll back to", file_name))?;
+    // an update staged since would put the rolled back version right back in place
+    if updates.staged.remove(&file_name).is_some() {
+        let _ = fs::remove_file(dirs.staged_updates()?.join(&file_name));
+    }
+    let previous_path = dirs.previous_mods()?.join(&file_name);
+
+    let mut journal = Journal::begin(dirs, "rollback_mod_update")?;
+    journal.stage_write(installed_path(&dirs.mods()?, &file_name), &fs::read(&previous_path)?)?;
+    journal.stage_remove(previous_path)?;
+    journal.commit()?;
+
+    if let Some(source) = updates.sources.get_mut(&file_name) {
+        source.skipped_version = source.installed_version.take();
+        source.installed_version = previous.version;
+    }
+    write_updates(dirs, &updates)
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub async fn rollback_mod_update(file_name: String, app: AppHandle) -> Result<()> {
+    tauri::async_runtime::spawn_blocking(move || rollback(file_name, &app.state::<AppState>())).await?
+}
@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::State;
+
+use crate::disk_space::ensure_free_space;
+use crate::error::Result;
+use crate::faults::{self, Fault};
+use crate::journal::write_atomic;
+use crate::paths::WeaveDirs;
+use crate::{mod_config, AppState, ModConfig};
+
+// Mods are disabled the same way the frontend always did it, by renaming foo.jar to
+// foo.jar.disabled in place, so profiles and share links keep referring to "foo.jar".
+
+pub const DISABLED_SUFFIX: &str = ".disabled";
+
+// same shape as the frontend's Mod
+#[derive(Serialize)]
+pub struct InstalledMod {
+    file_name: String,
+    // path of the enabled jar, even while the mod is disabled
+    file_path: PathBuf,
+    disabled: bool,
+    mod_info: ModConfig
+}
+
+// enabled mods sharing an id, only one of them can be loaded
+#[derive(Serialize)]
+pub struct DuplicateMod {
+    id: String,
+    file_names: Vec<String>
+}
+
+#[derive(Serialize)]
+pub struct ModList {
+    mods: Vec<InstalledMod>,
+    duplicates: Vec<DuplicateMod>
+}
+
+// rejects anything that would point outside the mods folder, names also come from share links
+pub fn check_file_name(file_name: &str) -> Result<()> {
+    if !file_name.ends_with(".jar") || Path::new(file_name).file_name().and_then(|name| name.to_str()) != Some(file_name) {
+        Err(format!("Invalid mod file name \"{}\"", file_name))?;
+    }
+    Ok(())
+}
+
+fn mod_path(dirs: &WeaveDirs, file_name: &str) -> Result<PathBuf> {
+    check_file_name(file_name)?;
+    Ok(dirs.mods()?.join(file_name))
+}
+
+fn disabled_path(path: &Path) -> PathBuf {
+    let mut disabled = path.as_os_str().to_owned();
+    disabled.push(DISABLED_SUFFIX);
+    PathBuf::from(disabled)
+}
+
+fn read_mod(dirs: &WeaveDirs, file_name: String, file_path: PathBuf, disabled: bool) -> InstalledMod {
+    let jar = if disabled { disabled_path(&file_path) } else { file_path.clone() };
+    InstalledMod {
+        mod_info: mod_config(dirs, &jar).unwrap_or_default(),
+        file_name,
+        file_path,
+        disabled
+    }
+}
+
+// older mods have no id in weave.mod.json, their name is the closest thing to one
+fn mod_id(config: &ModConfig) -> Option<&str> {
+    match &config.id {
+        Some(id) => Some(id.as_str()),
+        None if config.name != ModConfig::default().name => Some(config.name.as_str()),
+        None => None
+    }
+}
+
+fn find_duplicates(mods: &[InstalledMod]) -> Vec<DuplicateMod> {
+    let mut by_id: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for installed in mods.iter().filter(|installed| !installed.disabled) {
+        if let Some(id) = mod_id(&installed.mod_info) {
+            by_id.entry(id).or_default().push(installed.file_name.clone());
+        }
+    }
+
+    by_id.into_iter()
+        .filter(|(_, file_names)| file_names.len() > 1)
+        .map(|(id, file_names)| DuplicateMod { id: id.to_string(), file_names })
+        .collect()
+}
+
+pub fn list_mods(dirs: &WeaveDirs) -> Result<ModList> {
+    let mods_dir = dirs.mods()?;
+
+    let mut mods = Vec::new();
+    for entry in fs::read_dir(&mods_dir)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue
+        }
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue
+        };
+
+        let (file_name, disabled) = match name.strip_suffix(DISABLED_SUFFIX) {
+            Some(file_name) => (file_name.to_string(), true),
+            None => (name.to_string(), false)
+        };
+        // a disabled copy next to its enabled jar is an orphan left for cleanup
+        if !file_name.ends_with(".jar") || (disabled && mods_dir.join(&file_name).exists()) {
+            continue
+        }
+        mods.push(read_mod(dirs, file_name.clone(), mods_dir.join(file_name), disabled));
+    }
+    mods.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+
+    Ok(ModList {
+        duplicates: find_duplicates(&mods),
+        mods
+    })
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn get_mods(app_state: State<AppState>) -> Result<ModList> {
+    list_mods(&app_state.dirs)
+}
+
+// flips the mod between enabled and disabled and returns it in its new state
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn toggle_mod(file_name: String, app_state: State<AppState>) -> Result<InstalledMod> {
+    let enabled = mod_path(&app_state.dirs, &file_name)?;
+    let disabled = disabled_path(&enabled);
+
+    faults::inject_from(&app_state.dirs, Fault::DiskWrite)?;
+    let now_disabled = if enabled.exists() {
+        if disabled.exists() {
+            Err(format!("A disabled copy of {} is in the way, remove it first", file_name))?;
+        }
+        fs::rename(&enabled, &disabled)?;
+        true
+    } else if disabled.exists() {
+        fs::rename(&disabled, &enabled)?;
+        false
+    } else {
+        return Err(format!("Mod {} is not installed", file_name).into())
+    };
+
+    Ok(read_mod(&app_state.dirs, file_name, enabled, now_disabled))
+}
+
+// copies a jar into the mods folder, it has to be a readable zip so a broken download
+// never reaches the game
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn install_mod_from_path(path: String, app_state: State<AppState>) -> Result<InstalledMod> {
+    let source = PathBuf::from(path);
+    let file_name = source.file_name().and_then(|name| name.to_str()).unwrap_or_default().to_string();
+    let target = mod_path(&app_state.dirs, &file_name)?;
+    if target.exists() || disabled_path(&target).exists() {
+        Err(format!("A mod named {} is already installed", file_name))?;
+    }
+    mod_config(&app_state.dirs, &source)?;
+
+    ensure_free_space(&app_state.dirs, &target, fs::metadata(&source)?.len())?;
+    faults::inject_from(&app_state.dirs, Fault::DiskWrite)?;
+    write_atomic(&target, &fs::read(&source)?)?;
+
+    Ok(read_mod(&app_state.dirs, file_name, target, false))
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn delete_mod(file_name: String, app_state: State<AppState>) -> Result<()> {
+    let enabled = mod_path(&app_state.dirs, &file_name)?;
+    let disabled = disabled_path(&enabled);
+    if !enabled.exists() && !disabled.exists() {
+        Err(format!("Mod {} is not installed", file_name))?;
+    }
+
+    faults::inject_from(&app_state.dirs, Fault::DiskWrite)?;
+    for path in [enabled, disabled] {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
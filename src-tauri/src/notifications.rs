@@ -0,0 +1,213 @@
+use std::fs::File;
+use std::path::PathBuf;
+use std::thread;
+use serde::{Serialize, Deserialize};
+use chrono::{Local, Timelike};
+use tauri::{AppHandle, Manager, State};
+use tauri::api::notification::Notification;
+use tracing::warn;
+
+use crate::error::Result;
+use crate::download::USER_AGENT;
+use crate::faults::{self, Fault};
+use crate::journal::write_atomic;
+use crate::paths::get_weave_directory;
+use crate::{instances, AppState};
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NotificationEvent {
+    WeaveSpawned,
+    WeaveExited,
+    WeaveCrashed,
+    // a running game was relaunched with Weave by the auto-attach watcher
+    WeaveAutoAttached,
+    // the watcher stopped a game but could not relaunch it with Weave
+    AutoAttachFailed
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NotificationChannel {
+    // in-app toast rendered by the frontend
+    Toast,
+    // native desktop notification, shown as a tray balloon on Windows
+    Tray,
+    Webhook,
+    Sound
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NotificationRule {
+    event: NotificationEvent,
+    channels: Vec<NotificationChannel>,
+    // only applies to instances running in this game directory when set
+    #[serde(default)]
+    game_dir: Option<String>,
+    #[serde(default = "default_enabled")]
+    enabled: bool
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct QuietHours {
+    start_hour: u32,
+    end_hour: u32
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NotificationRules {
+    #[serde(default)]
+    rules: Vec<NotificationRule>,
+    #[serde(default)]
+    quiet_hours: Option<QuietHours>,
+    #[serde(default)]
+    webhook_url: Option<String>
+}
+
+#[derive(Clone, Serialize)]
+struct NotificationPayload {
+    event: NotificationEvent,
+    title: String,
+    body: String
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Default for NotificationRules {
+    fn default() -> Self {
+        let toast = |event| NotificationRule {
+            event,
+            channels: vec![NotificationChannel::Toast],
+            game_dir: None,
+            enabled: true
+        };
+
+        NotificationRules {
+            rules: vec![
+                toast(NotificationEvent::WeaveSpawned),
+                toast(NotificationEvent::WeaveCrashed),
+                toast(NotificationEvent::WeaveAutoAttached),
+                toast(NotificationEvent::AutoAttachFailed)
+            ],
+            quiet_hours: None,
+            webhook_url: None
+        }
+    }
+}
+
+impl QuietHours {
+    fn contains(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            // wraps around midnight, e.g. 22 to 7
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+impl NotificationRules {
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+
+    // collects the channels of every rule matching the event, during quiet hours only
+    // in-app toasts are delivered
+    fn channels_for(&self, event: NotificationEvent, game_dir: Option<&str>) -> Vec<NotificationChannel> {
+        let quiet = self.quiet_hours.as_ref().is_some_and(|quiet| quiet.contains(Local::now().hour()));
+
+        let mut channels = Vec::new();
+        for rule in self.rules.iter().filter(|rule| rule.enabled && rule.event == event) {
+            if rule.game_dir.as_deref().is_some_and(|dir| Some(dir) != game_dir) {
+                continue
+            }
+            for &channel in &rule.channels {
+                if !channels.contains(&channel) && (!quiet || channel == NotificationChannel::Toast) {
+                    channels.push(channel);
+                }
+            }
+        }
+        channels
+    }
+}
+
+fn get_notification_rules_path() -> Result<PathBuf> {
+    Ok(get_weave_directory()?.join("notifications.json"))
+}
+
+pub fn read_rules() -> Result<NotificationRules> {
+    let rules_path = get_notification_rules_path()?;
+    if !rules_path.exists() {
+        return Ok(NotificationRules::default())
+    }
+    Ok(serde_json::from_reader(File::open(rules_path)?)?)
+}
+
+fn send_webhook(url: String, payload: NotificationPayload) {
+    thread::spawn(move || {
+        let result = ureq::post(&url)
+            .set("User-Agent", USER_AGENT)
+            .send_json(&payload);
+        if let Err(e) = result {
+            warn!("Failed to deliver notification webhook: {}", e);
+        }
+    });
+}
+
+// Single entry point for backend notifications. Which channels an event reaches is decided
+// by the user's rules, callers only describe what happened.
+pub fn notify(app: &AppHandle, event: NotificationEvent, game_dir: Option<&str>, title: &str, body: &str) {
+    let app_state = app.state::<AppState>();
+    let rules = app_state.notification_rules.lock().unwrap().clone();
+
+    let payload = NotificationPayload {
+        event,
+        title: title.to_string(),
+        body: body.to_string()
+    };
+
+    for channel in rules.channels_for(event, game_dir) {
+        let result = match channel {
+            NotificationChannel::Toast => app.emit_all("notification", payload.clone()).map_err(|e| e.to_string()),
+            NotificationChannel::Sound => app.emit_all("notification_sound", payload.clone()).map_err(|e| e.to_string()),
+            NotificationChannel::Tray => Notification::new(&app.config().tauri.bundle.identifier)
+                .title(title)
+                .body(body)
+                .show()
+                .map_err(|e| e.to_string()),
+            NotificationChannel::Webhook => {
+                if let Some(url) = rules.webhook_url.clone() {
+                    send_webhook(url, payload.clone());
+                }
+                Ok(())
+            }
+        };
+
+        if let Err(e) = result {
+            warn!("Failed to deliver notification: {}", e);
+        }
+    }
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+pub fn get_notification_rules(app_state: State<AppState>) -> NotificationRules {
+    app_state.notification_rules.lock().unwrap().clone()
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn set_notification_rules(mut rules: NotificationRules, app_state: State<AppState>) -> Result<()> {
+    if rules.quiet_hours.as_ref().is_some_and(|quiet| quiet.start_hour > 23 || quiet.end_hour > 23) {
+        Err("Quiet hours must start and end between 0 and 23")?;
+    }
+    // compared against the normalized game directory of running instances
+    for rule in &mut rules.rules {
+        rule.game_dir = rule.game_dir.as_deref().map(instances::normalize_game_dir);
+    }
+
+    faults::inject(Fault::DiskWrite)?;
+    write_atomic(&get_notification_rules_path()?, &serde_json::to_vec(&rules)?)?;
+    *app_state.notification_rules.lock().unwrap() = rules;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+use serde::Serialize;
+use sysinfo::{Pid, PidExt, ProcessExt, SystemExt};
+use tauri::{AppHandle, Manager, WindowBuilder, WindowUrl};
+
+use crate::error::Result;
+use crate::AppState;
+
+pub const OVERLAY_LABEL: &str = "overlay";
+pub const OVERLAY_SHORTCUT: &str = "CmdOrCtrl+Shift+O";
+
+// agents report their frame rate by printing this marker followed by the value
+const FPS_MARKER: &str = "[weave-fps]";
+
+#[derive(Default)]
+pub struct OverlayState {
+    fps: Mutex<HashMap<u32, u32>>,
+    last_error: Mutex<HashMap<u32, String>>
+}
+
+#[derive(Clone, Serialize)]
+struct OverlayStats {
+    pid: Option<u32>,
+    fps: Option<u32>,
+    memory: Option<u64>,
+    session_time: Option<u64>,
+    last_error: Option<String>
+}
+
+impl OverlayState {
+    // number of pids the overlay currently holds fps and error entries for
+    pub fn buffer_sizes(&self) -> (usize, usize) {
+        (self.fps.lock().unwrap().len(), self.last_error.lock().unwrap().len())
+    }
+}
+
+// called for every console line of a weave process to pick up overlay relevant information
+pub fn inspect_line(overlay: &OverlayState, pid: u32, line: &str) {
+    if let Some(fps) = line.split_once(FPS_MARKER).and_then(|(_, fps)| fps.trim().parse().ok()) {
+        overlay.fps.lock().unwrap().insert(pid, fps);
+    } else if line.contains("/ERROR]") || line.contains("Exception") {
+        overlay.last_error.lock().unwrap().insert(pid, line.to_string());
+    }
+}
+
+pub fn forget(overlay: &OverlayState, pid: u32) {
+    overlay.fps.lock().unwrap().remove(&pid);
+    overlay.last_error.lock().unwrap().remove(&pid);
+}
+
+fn collect_stats(app_state: &AppState) -> OverlayStats {
+    let pid = app_state.selected_process.load(Ordering::Relaxed);
+    if pid == 0 {
+        return OverlayStats { pid: None, fps: None, memory: None, session_time: None, last_error: None }
+    }
+
+    let mut system = app_state.system.lock().unwrap();
+    system.refresh_process(Pid::from_u32(pid));
+    let process = system.process(Pid::from_u32(pid));
+
+    OverlayStats {
+        pid: Some(pid),
+        fps: app_state.overlay.fps.lock().unwrap().get(&pid).copied(),
+        memory: process.map(|p| p.memory()),
+        session_time: process.map(|p| p.run_time()),
+        last_error: app_state.overlay.last_error.lock().unwrap().get(&pid).cloned()
+    }
+}
+
+pub fn toggle(app: &AppHandle) -> Result<()> {
+    if let Some(window) = app.get_window(OVERLAY_LABEL) {
+        window.close()?;
+        return Ok(())
+    }
+
+    WindowBuilder::new(app, OVERLAY_LABEL, WindowUrl::App("overlay.html".into()))
+        .title("Weave Overlay")
+        .inner_size(240.0, 130.0)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .build()?;
+
+    // feed the overlay until its window is closed again
+    let app = app.clone();
+    thread::spawn(move || {
+        while app.get_window(OVERLAY_LABEL).is_some() {
+            let stats = collect_stats(&app.state::<AppState>());
+            if app.emit_to(OVERLAY_LABEL, "overlay_stats", stats).is_err() {
+                break
+            }
+            thread::sleep(Duration::from_secs(1));
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub async fn toggle_overlay(app: AppHandle) -> Result<()> {
+    toggle(&app)
+}
@@ -0,0 +1,133 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::api::path::home_dir;
+
+use crate::error::Result;
+
+// Debug builds can relocate the .weave directory, e.g. to run the manager against a scratch
+// copy. Release builds always use ~/.weave, which is where the frontend's fs scope points.
+const WEAVE_HOME_ENV: &str = "WEAVE_HOME";
+
+// Root of everything the manager keeps on disk. Commands get it from the AppState and pass it
+// down, so their logic can also run against a temporary directory with fake mods, a fake
+// loader jar and fake logs.
+#[derive(Clone)]
+pub struct WeaveDirs {
+    root: PathBuf
+}
+
+impl WeaveDirs {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        WeaveDirs { root: root.into() }
+    }
+
+    pub fn from_home() -> Result<Self> {
+        if cfg!(debug_assertions) {
+            if let Some(root) = env::var_os(WEAVE_HOME_ENV).filter(|root| !root.is_empty()) {
+                return Ok(WeaveDirs::new(root))
+            }
+        }
+        Ok(WeaveDirs::new(home_dir().ok_or("Home directory not found")?.join(".weave")))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    // resolves a directory inside .weave, creating it and any missing parents
+    fn subdirectory(&self, path: &[&str]) -> Result<PathBuf> {
+        let dir = path.iter().fold(self.root.clone(), |dir, part| dir.join(part));
+        if !dir.exists() {
+            fs::create_dir_all(&dir)?;
+        }
+        Ok(dir)
+    }
+
+    pub fn client_logs(&self) -> Result<PathBuf> {
+        self.subdirectory(&["logs", "client"])
+    }
+
+    pub fn history_logs(&self) -> Result<PathBuf> {
+        self.subdirectory(&["logs", "history"])
+    }
+
+    pub fn journal(&self) -> Result<PathBuf> {
+        self.subdirectory(&["journal"])
+    }
+
+    pub fn manager_logs(&self) -> Result<PathBuf> {
+        self.subdirectory(&["logs", "manager"])
+    }
+
+    pub fn mods(&self) -> Result<PathBuf> {
+        self.subdirectory(&["mods"])
+    }
+
+    pub fn profiles(&self) -> Result<PathBuf> {
+        self.subdirectory(&["profiles"])
+    }
+
+    pub fn recordings(&self) -> Result<PathBuf> {
+        self.subdirectory(&["recordings"])
+    }
+
+    pub fn updates(&self) -> Result<PathBuf> {
+        self.subdirectory(&["updates"])
+    }
+
+    pub fn staged_updates(&self) -> Result<PathBuf> {
+        self.subdirectory(&["updates", "staged"])
+    }
+
+    pub fn previous_mods(&self) -> Result<PathBuf> {
+        self.subdirectory(&["updates", "previous"])
+    }
+
+    pub fn wrappers(&self) -> Result<PathBuf> {
+        self.subdirectory(&["wrappers"])
+    }
+
+    pub fn loader(&self) -> Result<PathBuf> {
+        let loader_path = self.root.join("loader.jar");
+        if !loader_path.exists() {
+            Err(format!("Weave-Loader JAR file ({}) not found", loader_path.display()))?;
+        }
+        Ok(loader_path)
+    }
+}
+
+// modules that don't take a WeaveDirs yet always work on the real .weave directory
+
+pub fn get_weave_directory() -> Result<PathBuf> {
+    Ok(WeaveDirs::from_home()?.root)
+}
+
+pub fn get_weave_client_logs_path() -> Result<PathBuf> {
+    WeaveDirs::from_home()?.client_logs()
+}
+
+pub fn get_weave_history_logs_path() -> Result<PathBuf> {
+    WeaveDirs::from_home()?.history_logs()
+}
+
+pub fn get_weave_manager_logs_path() -> Result<PathBuf> {
+    WeaveDirs::from_home()?.manager_logs()
+}
+
+pub fn get_weave_mods_path() -> Result<PathBuf> {
+    WeaveDirs::from_home()?.mods()
+}
+
+pub fn get_weave_profiles_path() -> Result<PathBuf> {
+    WeaveDirs::from_home()?.profiles()
+}
+
+pub fn get_weave_recordings_path() -> Result<PathBuf> {
+    WeaveDirs::from_home()?.recordings()
+}
+
+pub fn get_weave_wrappers_path() -> Result<PathBuf> {
+    WeaveDirs::from_home()?.wrappers()
+}
+
@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+use serde::Serialize;
+
+use crate::error::Result;
+
+#[derive(Clone, Copy, Serialize)]
+pub enum PermissionProblem {
+    // owned by another user, usually root after running a launcher with sudo
+    WrongOwner,
+    NotWritable,
+    // could not be inspected at all, e.g. a directory only root may list
+    Unreadable
+}
+
+#[derive(Serialize)]
+pub struct PermissionFix {
+    path: PathBuf,
+    problem: PermissionProblem,
+    fixed: bool,
+    error: Option<String>
+}
+
+#[derive(Serialize)]
+pub struct PermissionReport {
+    checked: u32,
+    fixes: Vec<PermissionFix>,
+    // manual step for issues that could not be repaired without elevated privileges
+    hint: Option<String>
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::fs;
+    use std::os::unix::fs::{chown, MetadataExt, PermissionsExt};
+    use std::path::Path;
+    use tauri::api::path::home_dir;
+
+    use super::{PermissionFix, PermissionProblem, PermissionReport};
+    use crate::error::Result;
+    use crate::paths::get_weave_directory;
+
+    struct Owner {
+        uid: u32,
+        gid: u32
+    }
+
+    fn unreadable(path: &Path, error: std::io::Error) -> PermissionFix {
+        PermissionFix {
+            path: path.to_path_buf(),
+            problem: PermissionProblem::Unreadable,
+            fixed: false,
+            error: Some(error.to_string())
+        }
+    }
+
+    // problems are recorded in the report and the walk goes on, one root owned directory
+    // must not hide everything else that needs fixing
+    fn check(path: &Path, owner: &Owner, report: &mut PermissionReport) {
+        let metadata = match fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                report.fixes.push(unreadable(path, e));
+                return
+            }
+        };
+        if metadata.file_type().is_symlink() {
+            return
+        }
+        report.checked += 1;
+
+        let mut owned = metadata.uid() == owner.uid;
+        if !owned {
+            let result = chown(path, Some(owner.uid), Some(owner.gid));
+            owned = result.is_ok();
+            report.fixes.push(PermissionFix {
+                path: path.to_path_buf(),
+                problem: PermissionProblem::WrongOwner,
+                fixed: result.is_ok(),
+                error: result.err().map(|e| e.to_string())
+            });
+        }
+
+        // directories need to be traversable as well as writable
+        let required = if metadata.is_dir() { 0o700 } else { 0o600 };
+        let mode = metadata.permissions().mode();
+        if owned && mode & required != required {
+            let result = fs::set_permissions(path, fs::Permissions::from_mode(mode | required));
+            report.fixes.push(PermissionFix {
+                path: path.to_path_buf(),
+                problem: PermissionProblem::NotWritable,
+                fixed: result.is_ok(),
+                error: result.err().map(|e| e.to_string())
+            });
+        }
+
+        if metadata.is_dir() {
+            // read after the fixes above, which may just have made it listable
+            match fs::read_dir(path) {
+                Ok(entries) => for entry in entries {
+                    match entry {
+                        Ok(entry) => check(&entry.path(), owner, report),
+                        Err(e) => report.fixes.push(unreadable(path, e))
+                    }
+                },
+                Err(e) => report.fixes.push(unreadable(path, e))
+            }
+        }
+    }
+
+    pub fn repair() -> Result<PermissionReport> {
+        let weave_dir = get_weave_directory()?;
+
+        // the home directory tells us who .weave is supposed to belong to, even when
+        // the manager itself was started through sudo
+        let home = fs::metadata(home_dir().ok_or("Home directory not found")?)?;
+        let owner = Owner {
+            uid: home.uid(),
+            gid: home.gid()
+        };
+
+        let mut report = PermissionReport {
+            checked: 0,
+            fixes: Vec::new(),
+            hint: None
+        };
+        if weave_dir.exists() {
+            check(&weave_dir, &owner, &mut report);
+        }
+
+        if report.fixes.iter().any(|fix| !fix.fixed) {
+            report.hint = Some(format!(
+                "Some files could not be repaired, run `sudo chown -R {}:{} \"{}\"` and try again",
+                owner.uid, owner.gid, weave_dir.display()
+            ));
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use super::PermissionReport;
+    use crate::error::Result;
+
+    pub fn repair() -> Result<PermissionReport> {
+        Err("Permission repair is only available on Linux and macOS".into())
+    }
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn repair_permissions() -> Result<PermissionReport> {
+    platform::repair()
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::{Duration, Instant};
+use serde::{Serialize, Deserialize};
+use chrono::prelude::Local;
+use tauri::{AppHandle, Manager, State};
+use tracing::error;
+
+use crate::analytics::Session;
+use crate::error::Result;
+use crate::notifications::{self, NotificationEvent};
+use crate::paths::get_weave_recordings_path;
+use crate::{console, overlay, AppState, ClientType, WeaveProcess};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum RecordedEvent {
+    ProcessSpawned { pid: u32, client: ClientType, version: String },
+    ConsoleLine { pid: u32, line: String },
+    // written right before the exit of a game the manager took for a crash
+    ProcessCrashed { pid: u32 },
+    ProcessExited { pid: u32, code: Option<i32> }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RecordedEntry {
+    // milliseconds since the recording was started
+    at: u64,
+    event: RecordedEvent
+}
+
+struct ActiveRecording {
+    path: PathBuf,
+    started: Instant,
+    writer: BufWriter<File>
+}
+
+#[derive(Default)]
+pub struct RecordingState {
+    active: Mutex<Option<ActiveRecording>>
+}
+
+impl RecordingState {
+    pub fn active_path(&self) -> Option<PathBuf> {
+        self.active.lock().unwrap().as_ref().map(|active| active.path.clone())
+    }
+}
+
+// no-op unless a recording is running
+pub fn record(recording: &RecordingState, event: RecordedEvent) {
+    let mut active = recording.active.lock().unwrap();
+    let Some(active) = active.as_mut() else {
+        return
+    };
+
+    let entry = RecordedEntry {
+        at: active.started.elapsed().as_millis() as u64,
+        event
+    };
+    let result = serde_json::to_writer(&mut active.writer, &entry)
+        .map_err(io::Error::from)
+        .and_then(|_| writeln!(active.writer));
+    if let Err(e) = result {
+        error!("Failed to write to recording {}: {}", active.path.display(), e);
+    }
+}
+
+pub fn read_recording(path: &Path) -> Result<Vec<RecordedEntry>> {
+    BufReader::new(File::open(path)?)
+        .lines()
+        .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|line| -> Result<RecordedEntry> { Ok(serde_json::from_str(&line?)?) })
+        .collect()
+}
+
+// what the manager does in response to a recorded event, under the fake pid the replay uses
+pub enum ReplayAction {
+    Spawned { pid: u32, client: ClientType, version: String },
+    Line { pid: u32, line: String },
+    Exited { pid: u32, code: Option<i32>, crashed: bool }
+}
+
+struct ReplayedProcess {
+    pid: u32,
+    session: Session,
+    crash_recorded: bool
+}
+
+// Turns recorded events into manager actions. Crash detection runs on the replayed console
+// output like it does for a live game, so a replay also shows whether the manager still
+// reaches the verdict it reached while recording.
+#[derive(Default)]
+pub struct Replayer {
+    // recorded pids are mapped to fake ones so replays never touch real processes
+    processes: HashMap<u32, ReplayedProcess>
+}
+
+impl Replayer {
+    pub fn next(&mut self, entry: RecordedEntry) -> Option<ReplayAction> {
+        match entry.event {
+            RecordedEvent::ProcessSpawned { pid, client, version } => {
+                let fake_pid = console::next_fake_pid();
+                self.processes.insert(pid, ReplayedProcess {
+                    pid: fake_pid,
+                    session: Session::start(client.clone()),
+                    crash_recorded: false
+                });
+                Some(ReplayAction::Spawned { pid: fake_pid, client, version })
+            }
+            RecordedEvent::ConsoleLine { pid, line } => {
+                let process = self.processes.get_mut(&pid)?;
+                process.session.inspect_line(&line);
+                Some(ReplayAction::Line { pid: process.pid, line })
+            }
+            RecordedEvent::ProcessCrashed { pid } => {
+                self.processes.get_mut(&pid)?.crash_recorded = true;
+                None
+            }
+            RecordedEvent::ProcessExited { pid, code } => {
+                let process = self.processes.remove(&pid)?;
+                Some(ReplayAction::Exited {
+                    pid: process.pid,
+                    code,
+                    crashed: process.crash_recorded || process.session.crashed(code, false)
+                })
+            }
+        }
+    }
+
+    // fake pids of replayed games that haven't exited yet
+    fn running(&self) -> Vec<u32> {
+        self.processes.values().map(|process| process.pid).collect()
+    }
+}
+
+fn act(app: &AppHandle, action: ReplayAction) {
+    let app_state = app.state::<AppState>();
+    match action {
+        ReplayAction::Spawned { pid, client, version } => {
+            app_state.console.track_instance(pid, format!("Replay of Minecraft {}", version));
+            app_state.selected_process.store(pid, Ordering::Relaxed);
+            app.emit_all("spawned_weave", WeaveProcess {
+                log_file: PathBuf::new(),
+                client,
+                pid,
+                output: Vec::new()
+            }).expect("Failed to emit spawned_weave event to renderer");
+        }
+        ReplayAction::Line { pid, line } => console::emit_line(app, pid, line),
+        ReplayAction::Exited { pid, code, crashed } => {
+            overlay::forget(&app_state.overlay, pid);
+            console::emit_exit(app, pid, code);
+            if crashed {
+                notifications::notify(app, NotificationEvent::WeaveCrashed, None,
+                    "Minecraft crashed", &format!("Replayed Minecraft (PID {}) crashed", pid));
+            } else {
+                notifications::notify(app, NotificationEvent::WeaveExited, None,
+                    "Minecraft closed", &format!("Replayed Minecraft (PID {}) exited", pid));
+            }
+        }
+    }
+}
+
+// runs until the recording ends or stop_console_replay removes `replay_id`
+fn replay(app: &AppHandle, entries: Vec<RecordedEntry>, replay_id: u32, speed: f64) {
+    let app_state = app.state::<AppState>();
+    let mut replayer = Replayer::default();
+    let mut last_at = 0;
+    for entry in entries {
+        if speed > 0.0 {
+            thread::sleep(Duration::from_secs_f64(entry.at.saturating_sub(last_at) as f64 / 1000.0 / speed));
+        }
+        last_at = entry.at;
+        if !app_state.console.is_replaying(replay_id) {
+            break
+        }
+
+        if let Some(action) = replayer.next(entry) {
+            act(app, action);
+        }
+    }
+
+    // a stopped replay or a recording cut short leaves games behind that never exit
+    for pid in replayer.running() {
+        overlay::forget(&app_state.overlay, pid);
+        console::emit_exit(app, pid, None);
+    }
+    app_state.console.finish_replay(replay_id);
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn start_recording(app_state: State<AppState>) -> Result<PathBuf> {
+    let mut active = app_state.recording.active.lock().unwrap();
+    if active.is_some() {
+        Err("A recording is already running")?;
+    }
+
+    let path = get_weave_recordings_path()?.join(Local::now().format("%Y-%m-%d-%H%M%S.jsonl").to_string());
+    *active = Some(ActiveRecording {
+        writer: BufWriter::new(File::create(&path)?),
+        path: path.clone(),
+        started: Instant::now()
+    });
+    Ok(path)
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn stop_recording(app_state: State<AppState>) -> Result<Option<PathBuf>> {
+    match app_state.recording.active.lock().unwrap().take() {
+        Some(mut active) => {
+            active.writer.flush()?;
+            Ok(Some(active.path))
+        }
+        None => Ok(None)
+    }
+}
+
+// Developer tool like replay_console_log, the returned id stops it through stop_console_replay.
+// Replayed games get their own fake pids.
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn replay_recording(path: String, speed: f64, app_state: State<AppState>, app: AppHandle) -> Result<u32> {
+    if !cfg!(debug_assertions) {
+        Err("Recording replay is only available in development builds")?;
+    }
+
+    let entries = read_recording(Path::new(&path))?;
+    let replay_id = app_state.console.start_replay();
+    thread::spawn(move || replay(&app, entries, replay_id, speed));
+    Ok(replay_id)
+}
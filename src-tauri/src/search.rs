@@ -0,0 +1,233 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::settings::read_settings;
+use crate::paths::{get_weave_client_logs_path, get_weave_history_logs_path, get_weave_profiles_path, WeaveDirs};
+use crate::mod_config;
+
+const MAX_RESULTS: usize = 50;
+
+#[derive(Serialize)]
+pub enum SearchResultKind {
+    Mod,
+    Profile,
+    Setting,
+    Log,
+    Session
+}
+
+#[derive(Serialize)]
+pub struct SearchResult {
+    kind: SearchResultKind,
+    title: String,
+    detail: String,
+    path: Option<PathBuf>,
+    score: i32
+}
+
+// Case-insensitive subsequence match. Consecutive characters, matches at the start of a word
+// and plain substring hits are rewarded, long candidates are slightly penalized.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let query = query.trim().to_lowercase();
+    let needle: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).collect();
+    if needle.is_empty() {
+        return None
+    }
+
+    let candidate = candidate.to_lowercase();
+    let chars: Vec<char> = candidate.chars().collect();
+
+    let mut score = 0;
+    let mut matched = 0;
+    let mut last_match: Option<usize> = None;
+    for (i, &c) in chars.iter().enumerate() {
+        if matched == needle.len() {
+            break
+        }
+        if c != needle[matched] {
+            continue
+        }
+
+        score += 1;
+        if last_match.is_some_and(|last| last + 1 == i) {
+            score += 5;
+        }
+        if i == 0 || !chars[i - 1].is_alphanumeric() {
+            score += 3;
+        }
+
+        last_match = Some(i);
+        matched += 1;
+    }
+
+    if matched < needle.len() {
+        return None
+    }
+
+    if candidate.contains(&query) {
+        score += 10;
+    }
+    Some(score - (chars.len() / 16) as i32)
+}
+
+fn best_score(query: &str, candidates: &[&str]) -> Option<i32> {
+    candidates.iter().filter_map(|candidate| fuzzy_score(query, candidate)).max()
+}
+
+fn read_dir_files(dir: &Path) -> Vec<PathBuf> {
+    match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file())
+            .collect(),
+        Err(_) => Vec::new()
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn search_mods(query: &str, results: &mut Vec<SearchResult>) -> Result<()> {
+    let dirs = WeaveDirs::from_home()?;
+    for path in read_dir_files(&dirs.mods()?) {
+        let file_name = file_name(&path);
+        if !file_name.contains(".jar") {
+            continue
+        }
+
+        let config = mod_config(&dirs, &path).unwrap_or_default();
+        let Some(score) = best_score(query, &[config.name.as_str(), file_name.as_str()]) else {
+            continue
+        };
+
+        results.push(SearchResult {
+            kind: SearchResultKind::Mod,
+            title: config.name,
+            detail: format!("{} ({})", file_name, config.version),
+            path: Some(path),
+            score
+        });
+    }
+    Ok(())
+}
+
+fn search_profiles(query: &str, results: &mut Vec<SearchResult>) -> Result<()> {
+    for path in read_dir_files(&get_weave_profiles_path()?) {
+        let file_name = file_name(&path);
+        let detail = if file_name.ends_with(".lprof") {
+            "Launch Profile"
+        } else if file_name.ends_with(".mprof") {
+            "Mod Profile"
+        } else if file_name.ends_with(".gprof") {
+            "Game Profile"
+        } else {
+            continue
+        };
+
+        let profile: Value = match File::open(&path).map(serde_json::from_reader::<_, Value>) {
+            Ok(Ok(profile)) => profile,
+            _ => continue
+        };
+        let name = profile["name"].as_str().unwrap_or(&file_name).to_string();
+        let Some(score) = best_score(query, &[name.as_str()]) else {
+            continue
+        };
+
+        results.push(SearchResult {
+            kind: SearchResultKind::Profile,
+            title: name,
+            detail: detail.to_string(),
+            path: Some(path),
+            score
+        });
+    }
+    Ok(())
+}
+
+fn search_settings(query: &str, results: &mut Vec<SearchResult>) -> Result<()> {
+    for (key, value) in read_settings()? {
+        let Some(score) = best_score(query, &[key.as_str(), key.replace('_', " ").as_str()]) else {
+            continue
+        };
+
+        results.push(SearchResult {
+            kind: SearchResultKind::Setting,
+            title: key,
+            detail: value.to_string(),
+            path: None,
+            score
+        });
+    }
+    Ok(())
+}
+
+fn search_logs(query: &str, results: &mut Vec<SearchResult>) -> Result<()> {
+    for path in read_dir_files(&get_weave_client_logs_path()?) {
+        let file_name = file_name(&path);
+        let Some(score) = best_score(query, &[file_name.as_str()]) else {
+            continue
+        };
+
+        let size = fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+        results.push(SearchResult {
+            kind: SearchResultKind::Log,
+            title: file_name,
+            detail: format!("{:.1} KB", size as f64 / 1000.0),
+            path: Some(path),
+            score
+        });
+    }
+    Ok(())
+}
+
+fn search_sessions(query: &str, results: &mut Vec<SearchResult>) -> Result<()> {
+    let history_path = get_weave_history_logs_path()?.join("history.log");
+    if !history_path.exists() {
+        return Ok(())
+    }
+
+    let history: Value = serde_json::from_reader(File::open(history_path)?)?;
+    for session in history["history"].as_array().into_iter().flatten() {
+        let info = &session["info"];
+        let client = info["client"].as_str().unwrap_or_default();
+        let version = info["version"].as_str().unwrap_or_default();
+        let cwd = info["cwd"].as_str().unwrap_or_default();
+
+        let title = format!("{} {}", client, version);
+        let Some(score) = best_score(query, &[title.as_str(), cwd]) else {
+            continue
+        };
+
+        results.push(SearchResult {
+            kind: SearchResultKind::Session,
+            title,
+            detail: format!("PID {} in {}", session["pid"], cwd),
+            path: None,
+            score
+        });
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn search(query: String) -> Result<Vec<SearchResult>> {
+    let mut results = Vec::new();
+    if query.trim().is_empty() {
+        return Ok(results)
+    }
+
+    search_mods(&query, &mut results)?;
+    search_profiles(&query, &mut results)?;
+    search_settings(&query, &mut results)?;
+    search_logs(&query, &mut results)?;
+    search_sessions(&query, &mut results)?;
+
+    results.sort_by(|a, b| b.score.cmp(&a.score));
+    results.truncate(MAX_RESULTS);
+    Ok(results)
+}
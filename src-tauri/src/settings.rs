@@ -0,0 +1,53 @@
+use std::fs::File;
+use std::path::PathBuf;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::error::Result;
+use crate::journal::write_atomic;
+use crate::paths::WeaveDirs;
+
+// manager.settings is owned by the frontend, so the backend only ever reads or patches
+// individual keys and leaves everything else untouched
+
+fn settings_path(dirs: &WeaveDirs) -> PathBuf {
+    dirs.root().join("manager.settings")
+}
+
+pub fn read_settings() -> Result<Map<String, Value>> {
+    read_settings_from(&WeaveDirs::from_home()?)
+}
+
+pub fn read_settings_from(dirs: &WeaveDirs) -> Result<Map<String, Value>> {
+    let settings_path = settings_path(dirs);
+    if !settings_path.exists() {
+        return Ok(Map::new())
+    }
+
+    match serde_json::from_reader(File::open(settings_path)?)? {
+        Value::Object(settings) => Ok(settings),
+        _ => Ok(Map::new())
+    }
+}
+
+pub fn set_setting<T: Serialize>(key: &str, value: T) -> Result<()> {
+    set_setting_from(&WeaveDirs::from_home()?, key, value)
+}
+
+// patches a single key, everything else is written back as the frontend left it
+pub fn set_setting_from<T: Serialize>(dirs: &WeaveDirs, key: &str, value: T) -> Result<()> {
+    let mut settings = read_settings_from(dirs)?;
+    settings.insert(key.to_string(), serde_json::to_value(value)?);
+
+    write_atomic(&settings_path(dirs), &serde_json::to_vec(&settings)?)
+}
+
+pub fn get_setting<T: DeserializeOwned>(key: &str) -> Option<T> {
+    get_setting_from(&WeaveDirs::from_home().ok()?, key)
+}
+
+pub fn get_setting_from<T: DeserializeOwned>(dirs: &WeaveDirs, key: &str) -> Option<T> {
+    let value = read_settings_from(dirs).ok()?.remove(key)?;
+    serde_json::from_value(value).ok()
+}
@@ -0,0 +1,251 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use serde::{Serialize, Deserialize};
+use data_encoding::BASE64URL_NOPAD;
+
+use crate::error::Result;
+use crate::download::download;
+use crate::faults::{self, Fault};
+use crate::journal::{write_atomic, Journal};
+use crate::mods::check_file_name;
+use crate::settings::get_setting;
+use crate::paths::{get_weave_mods_path, WeaveDirs};
+use crate::sha256_hex;
+
+const LINK_SCHEME: &str = "weave://import?";
+const SETUP_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct SharedSetup {
+    name: String,
+    loader_version: Option<String>,
+    jvm_args: Vec<String>,
+    mods: Vec<SharedMod>
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SharedMod {
+    file_name: String,
+    sha256: String,
+    url: Option<String>
+}
+
+// what the user picks when sharing, the hash is filled in from the jar on disk
+#[derive(Deserialize)]
+pub struct ShareModSource {
+    file_name: String,
+    url: Option<String>
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+pub enum ImportAction {
+    // already installed with a matching hash
+    Installed,
+    // not installed, will be downloaded
+    Download,
+    // installed with a different hash, will be replaced by the download
+    Replace,
+    // not installed and no download url was shared
+    Unavailable
+}
+
+#[derive(Serialize)]
+pub struct ModPreview {
+    file_name: String,
+    url: Option<String>,
+    action: ImportAction
+}
+
+#[derive(Serialize)]
+pub struct SetupPreview {
+    name: String,
+    loader_version: Option<String>,
+    installed_loader_version: Option<String>,
+    jvm_args: Vec<String>,
+    mods: Vec<ModPreview>
+}
+
+#[derive(Serialize)]
+pub struct ImportReport {
+    installed: Vec<String>,
+    unavailable: Vec<String>,
+    loader_version: Option<String>,
+    jvm_args: Vec<String>
+}
+
+// a sharable mod may currently be enabled or disabled
+fn find_mod(mods_dir: &Path, file_name: &str) -> Option<PathBuf> {
+    [mods_dir.join(file_name), mods_dir.join(format!("{}.disabled", file_name))]
+        .into_iter()
+        .find(|path| path.is_file())
+}
+
+fn create_setup(name: String, mods: Vec<ShareModSource>, jvm_args: Vec<String>) -> Result<SharedSetup> {
+    let mods_dir = get_weave_mods_path()?;
+    let mods = mods.into_iter()
+        .map(|source| -> Result<SharedMod> {
+            check_file_name(&source.file_name)?;
+            let path = find_mod(&mods_dir, &source.file_name)
+                .ok_or_else(|| format!("Mod {} is not installed", source.file_name))?;
+            Ok(SharedMod {
+                sha256: sha256_hex(File::open(path)?)?,
+                file_name: source.file_name,
+                url: source.url
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(SharedSetup {
+        name,
+        loader_version: get_setting("loader_version"),
+        jvm_args,
+        mods
+    })
+}
+
+fn encode_link(setup: &SharedSetup) -> Result<String> {
+    let data = BASE64URL_NOPAD.encode(&serde_json::to_vec(setup)?);
+    Ok(format!("{}v={}&data={}", LINK_SCHEME, SETUP_FORMAT_VERSION, data))
+}
+
+fn decode_link(link: &str) -> Result<SharedSetup> {
+    let query = link.trim().strip_prefix(LINK_SCHEME).ok_or("Not a weave:// import link")?;
+
+    let mut version = None;
+    let mut data = None;
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "v" => version = value.parse::<u32>().ok(),
+            "data" => data = Some(value),
+            _ => {}
+        }
+    }
+
+    if version != Some(SETUP_FORMAT_VERSION) {
+        Err("Unsupported setup link version")?;
+    }
+    let bytes = BASE64URL_NOPAD.decode(data.ok_or("Setup link contains no data")?.as_bytes())
+        .map_err(|e| format!("Malformed setup link: {}", e))?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+// imports accept either a weave:// link or the path of an exported setup file
+fn read_setup(source: &str) -> Result<SharedSetup> {
+    let setup: SharedSetup = if source.trim().starts_with("weave://") {
+        decode_link(source)?
+    } else {
+        serde_json::from_reader(File::open(source)?)?
+    };
+
+    // the names end up joined onto the mods folder, one that escapes it rejects the whole setup
+    for shared in &setup.mods {
+        check_file_name(&shared.file_name)?;
+    }
+    Ok(setup)
+}
+
+fn import_action(mods_dir: &Path, shared: &SharedMod) -> Result<ImportAction> {
+    let installed = match find_mod(mods_dir, &shared.file_name) {
+        Some(path) => Some(sha256_hex(File::open(path)?)?),
+        None => None
+    };
+
+    Ok(match (installed, &shared.url) {
+        (Some(hash), _) if hash.eq_ignore_ascii_case(&shared.sha256) => ImportAction::Installed,
+        (Some(_), Some(_)) => ImportAction::Replace,
+        (None, Some(_)) => ImportAction::Download,
+        (_, None) => ImportAction::Unavailable
+    })
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn create_setup_link(name: String, mods: Vec<ShareModSource>, jvm_args: Vec<String>) -> Result<String> {
+    encode_link(&create_setup(name, mods, jvm_args)?)
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn export_setup_file(name: String, mods: Vec<ShareModSource>, jvm_args: Vec<String>, path: String) -> Result<()> {
+    let setup = create_setup(name, mods, jvm_args)?;
+    write_atomic(Path::new(&path), &serde_json::to_vec_pretty(&setup)?)
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn preview_setup_import(source: String) -> Result<SetupPreview> {
+    let setup = read_setup(&source)?;
+    let mods_dir = get_weave_mods_path()?;
+
+    let mods = setup.mods.into_iter()
+        .map(|shared| -> Result<ModPreview> {
+            Ok(ModPreview {
+                action: import_action(&mods_dir, &shared)?,
+                file_name: shared.file_name,
+                url: shared.url
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(SetupPreview {
+        name: setup.name,
+        loader_version: setup.loader_version,
+        installed_loader_version: get_setting("loader_version"),
+        jvm_args: setup.jvm_args,
+        mods
+    })
+}
+
+fn import(source: &str) -> Result<ImportReport> {
+    let setup = read_setup(source)?;
+    let dirs = WeaveDirs::from_home()?;
+    let mods_dir = dirs.mods()?;
+
+    // download and verify everything before touching the mods folder
+    let mut downloads = Vec::new();
+    let mut unavailable = Vec::new();
+    for shared in &setup.mods {
+        match import_action(&mods_dir, shared)? {
+            ImportAction::Installed => {}
+            ImportAction::Unavailable => unavailable.push(shared.file_name.clone()),
+            ImportAction::Download | ImportAction::Replace => {
+                let url = shared.url.as_deref().unwrap_or_default();
+                let bytes = download(&dirs, url)?;
+                if !sha256_hex(bytes.as_slice())?.eq_ignore_ascii_case(&shared.sha256) {
+                    Err(format!("Checksum mismatch for {} downloaded from {}", shared.file_name, url))?;
+                }
+                downloads.push((shared, bytes));
+            }
+        }
+    }
+
+    // staged through the journal so an interrupted import never leaves a half replaced mods folder
+    let mut journal = Journal::begin(&dirs, "import_setup")?;
+    let mut installed = Vec::new();
+    for (shared, bytes) in downloads {
+        faults::inject_from(&dirs, Fault::DiskWrite)?;
+
+        // a stale disabled copy would shadow the new jar when toggled
+        let disabled = mods_dir.join(format!("{}.disabled", shared.file_name));
+        if disabled.exists() {
+            journal.stage_remove(disabled)?;
+        }
+
+        journal.stage_write(mods_dir.join(&shared.file_name), &bytes)?;
+        installed.push(shared.file_name.clone());
+    }
+    journal.commit()?;
+
+    Ok(ImportReport {
+        installed,
+        unavailable,
+        loader_version: setup.loader_version,
+        jvm_args: setup.jvm_args
+    })
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub async fn import_setup(source: String) -> Result<ImportReport> {
+    tauri::async_runtime::spawn_blocking(move || import(&source)).await?
+}
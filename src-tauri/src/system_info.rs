@@ -0,0 +1,157 @@
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use serde::Serialize;
+use sysinfo::{CpuExt, ProcessExt, SystemExt};
+use tauri::{AppHandle, Manager};
+use tauri::api::path::home_dir;
+
+use crate::error::Result;
+use crate::gpu::{self, Gpu};
+use crate::AppState;
+
+#[derive(Serialize)]
+pub struct CpuInfo {
+    brand: String,
+    physical_cores: Option<usize>,
+    logical_cores: usize
+}
+
+#[derive(Serialize)]
+pub struct JavaRuntime {
+    path: PathBuf,
+    // first line of `java -version`, None if it could not be run
+    version: Option<String>
+}
+
+#[derive(Serialize)]
+pub struct SystemInfo {
+    os_name: Option<String>,
+    os_version: Option<String>,
+    kernel_version: Option<String>,
+    arch: &'static str,
+    cpu: CpuInfo,
+    total_memory: u64,
+    available_memory: u64,
+    gpus: Vec<Gpu>,
+    java_runtimes: Vec<JavaRuntime>,
+    manager_version: String
+}
+
+const JAVA_EXECUTABLE: &str = if cfg!(windows) { "java.exe" } else { "java" };
+
+// directories that contain one installed JDK/JRE per child
+fn java_install_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if cfg!(windows) {
+        for program_files in ["ProgramFiles", "ProgramFiles(x86)"].iter().filter_map(env::var_os) {
+            let program_files = PathBuf::from(program_files);
+            for vendor in ["Java", "Eclipse Adoptium", "Microsoft", "Zulu", "BellSoft"] {
+                roots.push(program_files.join(vendor));
+            }
+        }
+    } else if cfg!(target_os = "macos") {
+        roots.push(PathBuf::from("/Library/Java/JavaVirtualMachines"));
+    } else {
+        roots.push(PathBuf::from("/usr/lib/jvm"));
+    }
+    if let Some(home) = home_dir() {
+        roots.push(home.join(".jdks"));
+    }
+    roots
+}
+
+fn java_in_home(home: &Path) -> PathBuf {
+    // macOS bundles keep the actual home in Contents/Home
+    let bundle_home = home.join("Contents").join("Home");
+    let home = if bundle_home.exists() { bundle_home } else { home.to_path_buf() };
+    home.join("bin").join(JAVA_EXECUTABLE)
+}
+
+fn java_on_path() -> Option<PathBuf> {
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(JAVA_EXECUTABLE))
+        .find(|java| java.is_file())
+}
+
+fn java_version(java: &Path) -> Option<String> {
+    let output = Command::new(java).arg("-version").output().ok()?;
+    // java prints its version to stderr
+    let output = String::from_utf8_lossy(&output.stderr);
+    output.lines().next().map(|line| line.trim().to_string())
+}
+
+fn detect_java_runtimes(running: Vec<PathBuf>) -> Vec<JavaRuntime> {
+    let mut candidates = running;
+    candidates.extend(env::var_os("JAVA_HOME").map(|home| java_in_home(Path::new(&home))));
+    candidates.extend(java_on_path());
+    for root in java_install_roots() {
+        let Ok(entries) = fs::read_dir(root) else {
+            continue
+        };
+        candidates.extend(entries.filter_map(|entry| entry.ok()).map(|entry| java_in_home(&entry.path())));
+    }
+
+    // the same runtime is usually reachable through symlinks and JAVA_HOME as well
+    let paths: BTreeSet<PathBuf> = candidates.into_iter()
+        .filter(|java| java.is_file())
+        .filter_map(|java| fs::canonicalize(java).ok())
+        .collect();
+
+    paths.into_iter()
+        .map(|path| JavaRuntime {
+            version: java_version(&path),
+            path
+        })
+        .collect()
+}
+
+fn system_info(app: &AppHandle) -> SystemInfo {
+    let app_state = app.state::<AppState>();
+    let mut system = app_state.system.lock().unwrap();
+    system.refresh_memory();
+    system.refresh_cpu();
+
+    let cpu = CpuInfo {
+        brand: system.global_cpu_info().brand().trim().to_string(),
+        physical_cores: system.physical_core_count(),
+        logical_cores: system.cpus().len()
+    };
+
+    // javas the game is running on right now, e.g. the runtimes bundled with launchers
+    let running_javas = system.processes().values()
+        .map(|process| process.exe().to_path_buf())
+        .filter(|exe| matches!(exe.file_name().and_then(|name| name.to_str()), Some("java" | "javaw" | "java.exe" | "javaw.exe")))
+        .map(|exe| exe.with_file_name(JAVA_EXECUTABLE))
+        .collect();
+
+    let info = SystemInfo {
+        os_name: system.name(),
+        os_version: system.long_os_version(),
+        kernel_version: system.kernel_version(),
+        arch: env::consts::ARCH,
+        cpu,
+        total_memory: system.total_memory(),
+        available_memory: system.available_memory(),
+        gpus: Vec::new(),
+        java_runtimes: Vec::new(),
+        manager_version: app.package_info().version.to_string()
+    };
+    // don't hold the system lock while shelling out
+    drop(system);
+
+    SystemInfo {
+        gpus: gpu::detect_gpus().to_vec(),
+        java_runtimes: detect_java_runtimes(running_javas),
+        ..info
+    }
+}
+
+// detecting java runtimes runs every one of them, so keep it off the main thread
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub async fn get_system_info(app: AppHandle) -> Result<SystemInfo> {
+    Ok(tauri::async_runtime::spawn_blocking(move || system_info(&app)).await?)
+}
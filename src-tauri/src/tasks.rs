@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+
+// Last outcome of every background loop, so dump_state can tell a stuck or failing watcher
+// from one that is simply switched off.
+
+#[derive(Clone, Serialize)]
+pub enum TaskOutcome {
+    Ok,
+    // the loop ran but its setting or feature flag is off
+    Disabled,
+    Failed(String)
+}
+
+#[derive(Clone, Serialize)]
+pub struct TaskStatus {
+    name: &'static str,
+    // unix seconds of the last completed run, None until the first one
+    last_run: Option<u64>,
+    last_status: Option<TaskOutcome>,
+    runs: u64
+}
+
+#[derive(Default)]
+pub struct TaskState {
+    tasks: Mutex<BTreeMap<&'static str, TaskStatus>>
+}
+
+impl TaskState {
+    // called once the loop is started, before its first run
+    pub fn register(&self, name: &'static str) {
+        self.tasks.lock().unwrap().entry(name).or_insert(TaskStatus {
+            name,
+            last_run: None,
+            last_status: None,
+            runs: 0
+        });
+    }
+
+    pub fn record(&self, name: &'static str, outcome: TaskOutcome) {
+        self.register(name);
+        let mut tasks = self.tasks.lock().unwrap();
+        let task = tasks.get_mut(name).unwrap();
+        task.last_run = SystemTime::now().duration_since(UNIX_EPOCH).ok().map(|now| now.as_secs());
+        task.last_status = Some(outcome);
+        task.runs += 1;
+    }
+
+    pub fn snapshot(&self) -> Vec<TaskStatus> {
+        self.tasks.lock().unwrap().values().cloned().collect()
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::{Serialize, Deserialize};
+use tracing::{span, Event, Level, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::error::Result;
+use crate::journal::write_atomic;
+use crate::paths::get_weave_directory;
+
+// Every tauri command is instrumented with this target, e.g.
+// #[tracing::instrument(target = "command", skip_all, err)]
+pub const COMMAND_TARGET: &str = "command";
+
+// saved at most this often, commands polled by the frontend would otherwise write on every call
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+// Stored in ~/.weave/telemetry.json, nothing ever leaves the user's machine. Loaded on first use
+// since the layer records calls before the rest of the app has started.
+static COMMAND_STATS: Mutex<Option<CommandStatsFile>> = Mutex::new(None);
+
+struct CommandStatsFile {
+    stats: BTreeMap<String, CommandStats>,
+    last_saved: Instant
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct CommandStats {
+    calls: u64,
+    errors: u64,
+    total_ms: f64,
+    max_ms: f64,
+    last_ms: f64
+}
+
+#[derive(Serialize)]
+pub struct CommandTelemetry {
+    command: String,
+    average_ms: f64,
+    #[serde(flatten)]
+    stats: CommandStats
+}
+
+struct CommandTiming {
+    started: Instant
+}
+
+pub struct TelemetryLayer;
+
+pub fn is_command(metadata: &Metadata<'_>) -> bool {
+    metadata.target() == COMMAND_TARGET
+}
+
+fn get_telemetry_path() -> Result<PathBuf> {
+    Ok(get_weave_directory()?.join("telemetry.json"))
+}
+
+fn read_stats() -> BTreeMap<String, CommandStats> {
+    get_telemetry_path().ok()
+        .and_then(|path| File::open(path).ok())
+        .and_then(|file| serde_json::from_reader(file).ok())
+        .unwrap_or_default()
+}
+
+fn write_stats(stats: &BTreeMap<String, CommandStats>) -> Result<()> {
+    write_atomic(&get_telemetry_path()?, &serde_json::to_vec(stats)?)
+}
+
+// runs `update` on the stats and saves them if `force` is set or the last save is old enough.
+// Save errors are ignored, logging them would re-enter the layer while the stats are locked.
+fn with_stats<T, F>(force: bool, update: F) -> T
+    where F: FnOnce(&mut BTreeMap<String, CommandStats>) -> T
+{
+    let mut file = COMMAND_STATS.lock().unwrap();
+    let file = file.get_or_insert_with(|| CommandStatsFile {
+        stats: read_stats(),
+        last_saved: Instant::now()
+    });
+
+    let result = update(&mut file.stats);
+    if force || file.last_saved.elapsed() >= SAVE_INTERVAL {
+        let _ = write_stats(&file.stats);
+        file.last_saved = Instant::now();
+    }
+    result
+}
+
+fn record_call(command: &str, elapsed: Duration) {
+    let elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+    with_stats(false, |stats| {
+        let entry = stats.entry(command.to_string()).or_default();
+        entry.calls += 1;
+        entry.total_ms += elapsed_ms;
+        entry.max_ms = entry.max_ms.max(elapsed_ms);
+        entry.last_ms = elapsed_ms;
+    });
+}
+
+fn record_error(command: &str) {
+    with_stats(false, |stats| stats.entry(command.to_string()).or_default().errors += 1);
+}
+
+impl<S> Layer<S> for TelemetryLayer where S: Subscriber + for<'a> LookupSpan<'a> {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if !is_command(attrs.metadata()) {
+            return
+        }
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(CommandTiming { started: Instant::now() });
+        }
+    }
+
+    // `err` on the instrument attribute emits an error event inside the command span
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if *event.metadata().level() != Level::ERROR {
+            return
+        }
+        if let Some(span) = ctx.event_span(event).filter(|span| is_command(span.metadata())) {
+            record_error(span.name());
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return
+        };
+        if let Some(timing) = span.extensions().get::<CommandTiming>() {
+            record_call(span.name(), timing.started.elapsed());
+        }
+    }
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+pub fn get_command_telemetry() -> Vec<CommandTelemetry> {
+    with_stats(false, |stats| {
+        stats.iter()
+            .map(|(command, stats)| CommandTelemetry {
+                command: command.clone(),
+                average_ms: if stats.calls > 0 { stats.total_ms / stats.calls as f64 } else { 0.0 },
+                stats: stats.clone()
+            })
+            .collect()
+    })
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all)]
+pub fn reset_command_telemetry() {
+    with_stats(true, |stats| stats.clear());
+}
@@ -0,0 +1,114 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::faults::{self, Fault};
+use crate::journal::write_atomic;
+use crate::paths::{get_weave_directory, get_weave_wrappers_path};
+
+// Wrappers stand in for the java executable in a launcher's or profile's settings and add
+// the Weave agent before handing over to the real java, so Weave stays injected without the
+// manager running. The loader path is checked every time the wrapper runs, so it always
+// picks up the loader currently installed and still launches the game if it was removed.
+
+const WRAPPER_EXTENSION: &str = if cfg!(windows) { "bat" } else { "sh" };
+
+#[derive(Serialize)]
+pub struct LauncherWrapper {
+    name: String,
+    path: PathBuf
+}
+
+fn wrapper_path(name: &str) -> Result<PathBuf> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        Err("Wrapper names may only contain letters, digits, '-' and '_'")?;
+    }
+    Ok(get_weave_wrappers_path()?.join(format!("{}.{}", name, WRAPPER_EXTENSION)))
+}
+
+#[cfg(windows)]
+fn wrapper_script(java: &str, loader: &Path) -> String {
+    format!(
+        "@echo off\r\n\
+        rem Generated by Weave Manager, remove it from the manager\r\n\
+        set \"LOADER={loader}\"\r\n\
+        if exist \"%LOADER%\" (\r\n    \"{java}\" \"-javaagent:%LOADER%\" %*\r\n) else (\r\n    \"{java}\" %*\r\n)\r\n",
+        java = java,
+        loader = loader.display()
+    )
+}
+
+#[cfg(not(windows))]
+fn wrapper_script(java: &str, loader: &Path) -> String {
+    // single quotes keep paths literal, embedded quotes are closed, escaped and reopened
+    let quote = |value: &str| format!("'{}'", value.replace('\'', r"'\''"));
+    format!(
+        "#!/bin/sh\n\
+        # Generated by Weave Manager, remove it from the manager\n\
+        LOADER={loader}\n\
+        if [ -f \"$LOADER\" ]; then\n    exec {java} \"-javaagent:$LOADER\" \"$@\"\nfi\n\
+        exec {java} \"$@\"\n",
+        java = quote(java),
+        loader = quote(&loader.to_string_lossy())
+    )
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn create_launcher_wrapper(name: String, java_path: String) -> Result<LauncherWrapper> {
+    if !Path::new(&java_path).is_file() {
+        Err(format!("Java executable ({}) not found", java_path))?;
+    }
+
+    let path = wrapper_path(&name)?;
+    let loader = get_weave_directory()?.join("loader.jar");
+
+    faults::inject(Fault::DiskWrite)?;
+    write_atomic(&path, wrapper_script(&java_path, &loader).as_bytes())?;
+    make_executable(&path)?;
+
+    Ok(LauncherWrapper { name, path })
+}
+
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn get_launcher_wrappers() -> Result<Vec<LauncherWrapper>> {
+    let mut wrappers = Vec::new();
+    for entry in fs::read_dir(get_weave_wrappers_path()?)? {
+        let path = entry?.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some(WRAPPER_EXTENSION) {
+            continue
+        }
+        if let Some(name) = path.file_stem().and_then(|name| name.to_str()) {
+            wrappers.push(LauncherWrapper { name: name.to_string(), path });
+        }
+    }
+    wrappers.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(wrappers)
+}
+
+// launchers still pointing at a removed wrapper need their java path set back by the user
+#[tauri::command]
+#[tracing::instrument(target = "command", skip_all, err)]
+pub fn remove_launcher_wrapper(name: String) -> Result<()> {
+    let path = wrapper_path(&name)?;
+    if !path.exists() {
+        Err(format!("Wrapper {} not found", name))?;
+    }
+    faults::inject(Fault::DiskWrite)?;
+    fs::remove_file(path)?;
+    Ok(())
+}
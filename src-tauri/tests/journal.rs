@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use serde_json::Value;
+use weave_manager::journal::{self, Journal};
+use weave_manager::paths::WeaveDirs;
+
+// a fresh root per test, tests run in parallel and must not share files
+fn temp_dirs(test: &str) -> WeaveDirs {
+    let root = std::env::temp_dir().join(format!("weave-manager-journal-{}-{}", test, process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    WeaveDirs::new(root)
+}
+
+fn journals(dirs: &WeaveDirs) -> Vec<PathBuf> {
+    fs::read_dir(dirs.journal().unwrap()).unwrap().map(|entry| entry.unwrap().path()).collect()
+}
+
+fn read(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok()
+}
+
+// two files replaced and one removed, the shape of most operations in the manager
+struct Files {
+    replaced: PathBuf,
+    created: PathBuf,
+    removed: PathBuf
+}
+
+fn old_files(dirs: &WeaveDirs) -> Files {
+    let files = Files {
+        replaced: dirs.root().join("replaced.json"),
+        created: dirs.root().join("nested").join("created.json"),
+        removed: dirs.root().join("removed.json")
+    };
+    fs::write(&files.replaced, "old").unwrap();
+    fs::write(&files.removed, "old").unwrap();
+    files
+}
+
+fn stage(dirs: &WeaveDirs, files: &Files) -> Journal {
+    let mut journal = Journal::begin(dirs, "test").unwrap();
+    journal.stage_write(files.replaced.clone(), b"new").unwrap();
+    journal.stage_write(files.created.clone(), b"new").unwrap();
+    journal.stage_remove(files.removed.clone()).unwrap();
+    journal
+}
+
+fn assert_old(files: &Files) {
+    assert_eq!(read(&files.replaced).as_deref(), Some("old"));
+    assert_eq!(read(&files.created), None);
+    assert_eq!(read(&files.removed).as_deref(), Some("old"));
+}
+
+fn assert_new(files: &Files) {
+    assert_eq!(read(&files.replaced).as_deref(), Some("new"));
+    assert_eq!(read(&files.created).as_deref(), Some("new"));
+    assert_eq!(read(&files.removed), None);
+}
+
+// leaves the staged journal on disk as if the manager died after writing `state`, with the
+// first `applied` steps done the way commit does them
+fn interrupt(dirs: &WeaveDirs, journal: Journal, state: &str, applied: &[&Path]) {
+    std::mem::forget(journal);
+    let pending = journals(dirs);
+    assert_eq!(pending.len(), 1);
+    let dir = &pending[0];
+    let manifest_path = dir.join("journal.json");
+    let mut manifest: Value = serde_json::from_slice(&fs::read(&manifest_path).unwrap()).unwrap();
+    manifest["state"] = Value::from(state);
+    fs::write(&manifest_path, serde_json::to_vec(&manifest).unwrap()).unwrap();
+
+    for (index, target) in applied.iter().enumerate() {
+        if target.exists() {
+            fs::rename(target, dir.join(format!("backup-{}", index))).unwrap();
+        }
+        fs::create_dir_all(target.parent().unwrap()).unwrap();
+        fs::rename(dir.join(format!("staged-{}", index)), target).unwrap();
+    }
+}
+
+fn setup(test: &str) -> (WeaveDirs, Files) {
+    let dirs = temp_dirs(test);
+    let files = old_files(&dirs);
+    (dirs, files)
+}
+
+#[test]
+fn commit_applies_every_step() {
+    let (dirs, files) = setup("commit");
+    stage(&dirs, &files).commit().unwrap();
+    assert_new(&files);
+    assert!(journals(&dirs).is_empty());
+}
+
+#[test]
+fn dropped_journal_touches_nothing() {
+    let (dirs, files) = setup("drop");
+    drop(stage(&dirs, &files));
+    assert_old(&files);
+    assert!(journals(&dirs).is_empty());
+}
+
+#[test]
+fn failed_commit_rolls_back() {
+    let (dirs, files) = setup("rollback");
+    let journal = stage(&dirs, &files);
+    // the second step can't create its parent directory, after the first one was applied
+    fs::write(files.created.parent().unwrap(), "in the way").unwrap();
+
+    assert!(journal.commit().is_err());
+    assert_old(&files);
+    assert!(journals(&dirs).is_empty());
+}
+
+#[test]
+fn recover_resumes_interrupted_commit() {
+    let (dirs, files) = setup("resume");
+    let journal = stage(&dirs, &files);
+    interrupt(&dirs, journal, "Committing", &[&files.replaced]);
+
+    journal::recover(&dirs).unwrap();
+    assert_new(&files);
+    assert!(journals(&dirs).is_empty());
+}
+
+#[test]
+fn recover_rolls_back_commit_that_cannot_resume() {
+    let (dirs, files) = setup("resume-failed");
+    let journal = stage(&dirs, &files);
+    interrupt(&dirs, journal, "Committing", &[&files.replaced]);
+    fs::write(files.created.parent().unwrap(), "in the way").unwrap();
+
+    journal::recover(&dirs).unwrap();
+    assert_old(&files);
+    assert!(journals(&dirs).is_empty());
+}
+
+#[test]
+fn recover_finishes_interrupted_rollback() {
+    let (dirs, files) = setup("finish-rollback");
+    let journal = stage(&dirs, &files);
+    interrupt(&dirs, journal, "RollingBack", &[&files.replaced, &files.created]);
+
+    journal::recover(&dirs).unwrap();
+    assert_old(&files);
+    assert!(journals(&dirs).is_empty());
+}
+
+#[test]
+fn recover_discards_unfinished_preparation() {
+    let (dirs, files) = setup("discard");
+    let journal = stage(&dirs, &files);
+    interrupt(&dirs, journal, "Preparing", &[]);
+
+    journal::recover(&dirs).unwrap();
+    assert_old(&files);
+    assert!(journals(&dirs).is_empty());
+}
@@ -0,0 +1,82 @@
+use std::fs;
+use std::process;
+
+use serde_json::json;
+use weave_manager::recording::{self, ReplayAction, Replayer};
+use weave_manager::ClientType;
+
+// replays a recorded session and returns what the manager did, in order
+fn replay(test: &str, events: &[serde_json::Value]) -> Vec<ReplayAction> {
+    let path = std::env::temp_dir().join(format!("weave-manager-{}-{}.jsonl", test, process::id()));
+    let lines: Vec<String> = events.iter().enumerate()
+        .map(|(at, event)| json!({ "at": at, "event": event }).to_string())
+        .collect();
+    fs::write(&path, lines.join("\n")).unwrap();
+
+    let mut replayer = Replayer::default();
+    recording::read_recording(&path).unwrap().into_iter()
+        .filter_map(|entry| replayer.next(entry))
+        .collect()
+}
+
+fn exit_of(actions: &[ReplayAction], fake_pid: u32) -> (Option<i32>, bool) {
+    actions.iter()
+        .find_map(|action| match action {
+            ReplayAction::Exited { pid, code, crashed } if *pid == fake_pid => Some((*code, *crashed)),
+            _ => None
+        })
+        .unwrap()
+}
+
+#[test]
+fn replay_detects_crashes_from_the_recorded_output() {
+    let actions = replay("replay-crashes", &[
+        json!({ "type": "ProcessSpawned", "pid": 100, "client": "Vanilla", "version": "1.8.9" }),
+        json!({ "type": "ProcessSpawned", "pid": 200, "client": "Forge", "version": "1.8.9" }),
+        json!({ "type": "ConsoleLine", "pid": 100, "line": "[12:00:00] [Client thread/INFO]: Sound engine started" }),
+        json!({ "type": "ConsoleLine", "pid": 200, "line": "---- Minecraft Crash Report ----" }),
+        // output of a game the recording didn't see start is dropped
+        json!({ "type": "ConsoleLine", "pid": 999, "line": "stray" }),
+        json!({ "type": "ProcessExited", "pid": 200, "code": null }),
+        json!({ "type": "ProcessExited", "pid": 100, "code": 0 })
+    ]);
+
+    let spawned: Vec<(u32, ClientType)> = actions.iter()
+        .filter_map(|action| match action {
+            ReplayAction::Spawned { pid, client, .. } => Some((*pid, client.clone())),
+            _ => None
+        })
+        .collect();
+    let [(vanilla, ClientType::Vanilla), (forge, ClientType::Forge)] = spawned.as_slice() else {
+        panic!("expected a Vanilla and a Forge game, got {:?}", spawned);
+    };
+    let (vanilla, forge) = (*vanilla, *forge);
+    assert!(![100, 200].contains(&vanilla) && ![100, 200].contains(&forge), "replays must not reuse recorded pids");
+    assert_ne!(vanilla, forge);
+
+    let lines: Vec<(u32, &str)> = actions.iter()
+        .filter_map(|action| match action {
+            ReplayAction::Line { pid, line } => Some((*pid, line.as_str())),
+            _ => None
+        })
+        .collect();
+    assert_eq!(lines, [(vanilla, "[12:00:00] [Client thread/INFO]: Sound engine started"), (forge, "---- Minecraft Crash Report ----")]);
+
+    assert_eq!(exit_of(&actions, forge), (None, true));
+    assert_eq!(exit_of(&actions, vanilla), (Some(0), false));
+}
+
+#[test]
+fn replay_keeps_recorded_crashes() {
+    // crashed while recording without a marker the manager knows today, e.g. an older marker list
+    let actions = replay("replay-recorded-crash", &[
+        json!({ "type": "ProcessSpawned", "pid": 100, "client": "Lunar", "version": "1.20.1" }),
+        json!({ "type": "ProcessCrashed", "pid": 100 }),
+        json!({ "type": "ProcessExited", "pid": 100, "code": 0 })
+    ]);
+
+    let ReplayAction::Spawned { pid, .. } = &actions[0] else {
+        panic!("the replay must start with the spawn");
+    };
+    assert_eq!(exit_of(&actions, *pid), (Some(0), true));
+}
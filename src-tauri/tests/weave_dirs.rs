@@ -0,0 +1,172 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use data_encoding::HEXUPPER;
+use ring::digest::{digest, SHA256};
+use serde_json::{json, Value};
+use weave_manager::paths::WeaveDirs;
+use weave_manager::{cleanup, loader, mods};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+// a fresh root per test, tests run in parallel and must not share files
+fn temp_dirs(test: &str) -> WeaveDirs {
+    let root = std::env::temp_dir().join(format!("weave-manager-{}-{}", test, process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    WeaveDirs::new(root)
+}
+
+fn write_mod(path: PathBuf, config: Value) {
+    let mut jar = ZipWriter::new(File::create(path).unwrap());
+    jar.start_file("weave.mod.json", FileOptions::default()).unwrap();
+    jar.write_all(config.to_string().as_bytes()).unwrap();
+    jar.finish().unwrap();
+}
+
+fn mod_json(id: &str) -> Value {
+    json!({
+        "id": id,
+        "name": id,
+        "version": "1.0.0",
+        "description": "",
+        "authors": []
+    })
+}
+
+fn sha256(bytes: &[u8]) -> String {
+    HEXUPPER.encode(digest(&SHA256, bytes).as_ref())
+}
+
+fn loader_status(dirs: &WeaveDirs) -> Value {
+    serde_json::to_value(loader::check_health(dirs).unwrap()).unwrap()["status"].clone()
+}
+
+fn exists(dir: &Path, file_name: &str) -> bool {
+    dir.join(file_name).exists()
+}
+
+#[test]
+fn lists_mods_and_reports_enabled_duplicates() {
+    let dirs = temp_dirs("mods");
+    let mods_dir = dirs.mods().unwrap();
+    write_mod(mods_dir.join("a.jar"), mod_json("shared"));
+    write_mod(mods_dir.join("b.jar"), mod_json("shared"));
+    write_mod(mods_dir.join("c.jar.disabled"), mod_json("shared"));
+    write_mod(mods_dir.join("d.jar"), mod_json("other"));
+
+    let list = serde_json::to_value(mods::list_mods(&dirs).unwrap()).unwrap();
+    let mods: Vec<(&str, bool)> = list["mods"].as_array().unwrap().iter()
+        .map(|installed| (installed["file_name"].as_str().unwrap(), installed["disabled"].as_bool().unwrap()))
+        .collect();
+    assert_eq!(mods, [("a.jar", false), ("b.jar", false), ("c.jar", true), ("d.jar", false)]);
+
+    // the disabled copy can't be loaded, so it doesn't count as a duplicate
+    assert_eq!(list["duplicates"], json!([{ "id": "shared", "file_names": ["a.jar", "b.jar"] }]));
+}
+
+#[test]
+fn loader_health_follows_the_jar_on_disk() {
+    let dirs = temp_dirs("loader");
+    assert_eq!(loader_status(&dirs), "Missing");
+
+    let jar = b"fake loader";
+    fs::write(dirs.root().join("loader.jar"), jar).unwrap();
+    assert_eq!(loader_status(&dirs), "Unverified");
+
+    let record = json!({ "version": "v1.0.0", "sha256": sha256(jar) });
+    fs::write(dirs.root().join("loader.json"), record.to_string()).unwrap();
+    assert_eq!(loader_status(&dirs), "Ok");
+
+    fs::write(dirs.root().join("loader.jar"), b"quarantined").unwrap();
+    assert_eq!(loader_status(&dirs), "Corrupted");
+}
+
+#[test]
+fn cleanup_removes_old_logs_and_orphaned_mods() {
+    let dirs = temp_dirs("cleanup");
+    let logs_dir = dirs.client_logs().unwrap();
+    let manager_logs_dir = dirs.manager_logs().unwrap();
+    let mods_dir = dirs.mods().unwrap();
+    fs::write(logs_dir.join("2023-07-01-120000.log"), "[12:00:00] [main/INFO]: Setting user").unwrap();
+    fs::write(logs_dir.join("notes.txt"), "kept").unwrap();
+    let todays_manager_log = format!("manager.log.{}", chrono::Utc::now().format("%Y-%m-%d"));
+    fs::write(manager_logs_dir.join("manager.log.2023-07-01"), "INFO weave_manager: started").unwrap();
+    fs::write(manager_logs_dir.join(&todays_manager_log), "INFO weave_manager: started").unwrap();
+    write_mod(mods_dir.join("a.jar"), mod_json("a"));
+    write_mod(mods_dir.join("a.jar.disabled"), mod_json("a"));
+    write_mod(mods_dir.join("b.jar.disabled"), mod_json("b"));
+
+    let options = |dry_run: bool| -> cleanup::CleanupOptions {
+        serde_json::from_value(json!({ "logs": true, "disabled_mods": true, "older_than_days": 0, "dry_run": dry_run })).unwrap()
+    };
+    let report = serde_json::to_value(cleanup::run_cleanup(&dirs, options(true)).unwrap()).unwrap();
+    assert_eq!(report["categories"][0]["files_removed"], 2);
+    assert_eq!(report["categories"][1]["files_removed"], 1);
+    assert!(exists(&logs_dir, "2023-07-01-120000.log"), "a dry run must not remove anything");
+
+    cleanup::run_cleanup(&dirs, options(false)).unwrap();
+    assert!(!exists(&logs_dir, "2023-07-01-120000.log"));
+    assert!(exists(&logs_dir, "notes.txt"));
+    assert!(!exists(&manager_logs_dir, "manager.log.2023-07-01"));
+    assert!(exists(&manager_logs_dir, &todays_manager_log), "the manager is still writing today's log");
+    assert!(!exists(&mods_dir, "a.jar.disabled"));
+    assert!(exists(&mods_dir, "a.jar"));
+    assert!(exists(&mods_dir, "b.jar.disabled"), "a disabled mod without an enabled copy isn't orphaned");
+}
+
+#[test]
+fn cleanup_removes_unreferenced_updates_and_leftover_journals() {
+    let dirs = temp_dirs("cleanup-caches");
+    let staged_dir = dirs.staged_updates().unwrap();
+    let previous_dir = dirs.previous_mods().unwrap();
+    let journal_dir = dirs.journal().unwrap();
+    fs::write(staged_dir.join("a.jar"), "staged").unwrap();
+    fs::write(staged_dir.join("b.jar"), "no longer staged").unwrap();
+    fs::write(previous_dir.join("a.jar"), "previous").unwrap();
+    fs::write(previous_dir.join("c.jar"), "rolled back").unwrap();
+    let updates = json!({
+        "staged": { "a.jar": { "version": "v2", "sha256": sha256(b"staged") } },
+        "previous": { "a.jar": { "version": "v1", "sha256": sha256(b"previous") } }
+    });
+    fs::write(dirs.updates().unwrap().join("updates.json"), updates.to_string()).unwrap();
+    // pid 0 is never a running manager, this one is in use by the current process
+    fs::create_dir_all(journal_dir.join("20230701-120000000-0-0")).unwrap();
+    fs::create_dir_all(journal_dir.join(format!("20230701-120000000-{}-0", process::id()))).unwrap();
+
+    let options = |dry_run: bool| -> cleanup::CleanupOptions {
+        serde_json::from_value(json!({ "caches": true, "quarantine": true, "older_than_days": 0, "dry_run": dry_run })).unwrap()
+    };
+    let report = serde_json::to_value(cleanup::run_cleanup(&dirs, options(true)).unwrap()).unwrap();
+    assert_eq!(report["categories"][0]["files_removed"], 2);
+    assert_eq!(report["categories"][1]["files_removed"], 1);
+    assert!(exists(&staged_dir, "b.jar"), "a dry run must not remove anything");
+
+    cleanup::run_cleanup(&dirs, options(false)).unwrap();
+    assert!(exists(&staged_dir, "a.jar"));
+    assert!(!exists(&staged_dir, "b.jar"));
+    assert!(exists(&previous_dir, "a.jar"));
+    assert!(!exists(&previous_dir, "c.jar"));
+    assert!(!exists(&journal_dir, "20230701-120000000-0-0"));
+    assert!(exists(&journal_dir, &format!("20230701-120000000-{}-0", process::id())));
+}
+
+#[test]
+fn cleanup_finds_crash_dumps_in_known_game_dirs() {
+    let dirs = temp_dirs("crash-dumps");
+    let game_dir = dirs.root().join("minecraft");
+    fs::create_dir_all(game_dir.join("crash-reports")).unwrap();
+    fs::write(game_dir.join("hs_err_pid1234.log"), "# A fatal error has been detected").unwrap();
+    fs::write(game_dir.join("crash-reports").join("crash-2023-07-01_12.00.00-client.txt"), "---- Minecraft Crash Report ----").unwrap();
+    fs::write(game_dir.join("options.txt"), "kept").unwrap();
+    let nicknames = json!({ "hash": { "game_dir": game_dir.to_str().unwrap(), "nickname": "Main" } });
+    fs::write(dirs.root().join("instances.json"), nicknames.to_string()).unwrap();
+
+    let options = serde_json::from_value(json!({ "crash_dumps": true, "older_than_days": 0 })).unwrap();
+    let report = serde_json::to_value(cleanup::run_cleanup(&dirs, options).unwrap()).unwrap();
+    assert_eq!(report["categories"][0]["files_removed"], 2);
+    assert!(!exists(&game_dir, "hs_err_pid1234.log"));
+    assert!(exists(&game_dir, "options.txt"));
+}
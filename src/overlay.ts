@@ -0,0 +1,8 @@
+import './app.css'
+import Overlay from './components/Overlay.svelte'
+
+const overlay = new Overlay({
+  target: document.getElementById('overlay'),
+})
+
+export default overlay
@@ -1,5 +1,4 @@
 import type {
-    Analytics,
     GitHubApiResponse,
     LoaderUpdateResponse,
     MinecraftProcess,
@@ -7,22 +6,13 @@ import type {
     Settings
 } from "./types";
 import {fetch, ResponseType, Response} from "@tauri-apps/api/http"
-import {writeBinaryFile, writeFile, writeTextFile} from "@tauri-apps/api/fs";
+import {writeBinaryFile, writeTextFile} from "@tauri-apps/api/fs";
 import {getHistoryLogsDirectory, getWeaveDirectory} from "./paths";
 import {processHistory, processMap, settings} from "./stores";
 import {get} from "svelte/store";
 import {invoke} from "@tauri-apps/api/tauri";
 
 export async function installWeave() {
-    await writeFile(
-        `${await getWeaveDirectory()}/analytics.json`,
-        JSON.stringify(<Analytics> {
-            launch_times: [],
-            time_played: 0,
-            average_launch_time: 0.0
-        })
-    )
-
     const response = await fetchGithubApi()
     const loaderDownload = response.assets.filter(asset => asset.name.endsWith(".jar"))[0].browser_download_url
 
@@ -19,6 +19,7 @@ export interface Mod {
 }
 
 export interface ModInfo {
+    id?: string
     name: string
     version: string
     description: string
@@ -32,10 +33,13 @@ export interface Agent {
 }
 
 export interface MinecraftProcess {
+    id: string
     pid: number
     start_time: number
     info: MinecraftInfo
     weave_attached: boolean
+    game_dir: string
+    nickname?: string
 }
 
 export interface ProcessHistory {
@@ -49,14 +53,41 @@ export interface MinecraftInfo {
     cwd: string
 }
 
+export interface GameProfile {
+    name: string
+    client: string
+    version: string
+    java_path: string
+    working_dir: string
+    max_memory?: string
+    min_memory?: string
+    jvm_args: string[]
+    java_agents: string[]
+    classpath: string[]
+    main_class: string
+    game_args: string[]
+}
+
 export interface OptionButton {
     label: string
     icon: string
     action: () => void
 }
 
+export interface OverlayStats {
+    pid?: number
+    fps?: number
+    memory?: number
+    session_time?: number
+    last_error?: string
+}
+
 export interface ConsolePayload {
     line: string
+    pid: number
+    instance?: string
+    sequence: number
+    level?: "Debug" | "Info" | "Warn" | "Error"
 }
 
 export interface WeaveProcess {
@@ -66,10 +97,39 @@ export interface WeaveProcess {
     output: string[]
 }
 
-export interface Analytics {
+export interface ClientStats {
     launch_times: number[]
     time_played: number
     average_launch_time: number
+    sessions: number
+    crashes: number
+}
+
+export interface Analytics extends ClientStats {
+    clients: Record<string, ClientStats>
+}
+
+export interface InstanceMetrics {
+    pid: number
+    cpu_usage: number
+    memory: number
+    disk_io: DiskIo
+    // traffic of the whole machine (network namespace), not of this instance; Linux only
+    system_network?: SystemNetworkUsage
+}
+
+export interface DiskIo {
+    read_bytes: number
+    written_bytes: number
+    recent_read_bytes: number
+    recent_written_bytes: number
+}
+
+export interface SystemNetworkUsage {
+    rx_bytes: number
+    tx_bytes: number
+    rx_bytes_per_sec: number
+    tx_bytes_per_sec: number
 }
 
 export interface Settings {
@@ -21,5 +21,12 @@ export default defineConfig({
     minify: !process.env.TAURI_DEBUG ? 'esbuild' : false,
     // produce sourcemaps for debug builds
     sourcemap: !!process.env.TAURI_DEBUG,
+    // the overlay window loads its own page instead of the whole app
+    rollupOptions: {
+      input: {
+        main: 'index.html',
+        overlay: 'overlay.html',
+      },
+    },
   },
 })